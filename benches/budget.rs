@@ -0,0 +1,171 @@
+//! Reports the Soroban CPU instruction and memory cost of each `SorobanFixedPoint` and
+//! `SorobanFixedPointExt` operation.
+//!
+//! Run with `cargo bench --bench budget`. Costs are measured natively with `Env::default()`, so
+//! only host function costs are included. Operations on i128 and u128 take the native path when
+//! the intermediate product fits, which makes no host calls and reports a cost of 0, and fall
+//! back to I256 or U256 math otherwise. Fallback costs include the conversions to and from the
+//! host types.
+
+use core::hint::black_box;
+
+use soroban_fixed_point_math::SorobanFixedPointExt;
+use soroban_sdk::{Env, I256, U256};
+
+/// Inputs for each operation of a `SorobanFixedPoint` implementation
+struct Inputs<T> {
+    x: T,
+    y: T,
+    denominator: T,
+    fee_rate: T,
+    scale: T,
+    shift: u32,
+}
+
+/// Returns the CPU instructions and memory bytes consumed by `f`
+fn measure<R>(env: &Env, f: impl FnOnce() -> R) -> (u64, u64) {
+    let mut budget = env.cost_estimate().budget();
+    budget.reset_unlimited();
+    black_box(f());
+    (budget.cpu_instruction_cost(), budget.memory_bytes_cost())
+}
+
+/// Prints the cost of each operation for `inputs`
+fn report<T: SorobanFixedPointExt>(env: &Env, label: &str, inputs: &Inputs<T>) {
+    let Inputs {
+        x,
+        y,
+        denominator,
+        fee_rate,
+        scale,
+        shift,
+    } = inputs;
+    let costs = [
+        (
+            "fixed_mul_floor",
+            measure(env, || x.fixed_mul_floor(env, y, denominator)),
+        ),
+        (
+            "fixed_mul_ceil",
+            measure(env, || x.fixed_mul_ceil(env, y, denominator)),
+        ),
+        (
+            "fixed_div_floor",
+            measure(env, || x.fixed_div_floor(env, y, denominator)),
+        ),
+        (
+            "fixed_div_ceil",
+            measure(env, || x.fixed_div_ceil(env, y, denominator)),
+        ),
+        (
+            "gross_up",
+            measure(env, || x.gross_up(env, fee_rate, scale)),
+        ),
+        (
+            "net_down",
+            measure(env, || x.net_down(env, fee_rate, scale)),
+        ),
+        (
+            "fixed_mul_shr_floor",
+            measure(env, || x.fixed_mul_shr_floor(env, y, *shift)),
+        ),
+        (
+            "fixed_mul_shr_ceil",
+            measure(env, || x.fixed_mul_shr_ceil(env, y, *shift)),
+        ),
+        (
+            "fixed_div_shl_floor",
+            measure(env, || x.fixed_div_shl_floor(env, y, *shift)),
+        ),
+        (
+            "fixed_div_shl_ceil",
+            measure(env, || x.fixed_div_shl_ceil(env, y, *shift)),
+        ),
+    ];
+    for (operation, (cpu, mem)) in costs {
+        println!("{:<20} {:<22} {:>12} {:>12}", label, operation, cpu, mem);
+    }
+}
+
+fn main() {
+    let env = Env::default();
+    println!(
+        "{:<20} {:<22} {:>12} {:>12}",
+        "type", "operation", "cpu_insns", "mem_bytes"
+    );
+
+    // 7 decimal values whose products fit in 128 bits
+    report(
+        &env,
+        "i128 native",
+        &Inputs {
+            x: 15_391_283_000i128,
+            y: 314_1592653,
+            denominator: 1_0000001,
+            fee_rate: 30000,
+            scale: 1_0000000,
+            shift: 64,
+        },
+    );
+    // 18 decimal values whose products overflow 128 bits
+    report(
+        &env,
+        "i128 I256 fallback",
+        &Inputs {
+            x: i128::MAX / 3,
+            y: 1_000_000_000_000_000_000,
+            denominator: 1_000_000_000_000_000_001,
+            fee_rate: 3_000_000_000_000_000,
+            scale: 1_000_000_000_000_000_000,
+            shift: 60,
+        },
+    );
+    report(
+        &env,
+        "u128 native",
+        &Inputs {
+            x: 15_391_283_000u128,
+            y: 314_1592653,
+            denominator: 1_0000001,
+            fee_rate: 30000,
+            scale: 1_0000000,
+            shift: 64,
+        },
+    );
+    report(
+        &env,
+        "u128 U256 fallback",
+        &Inputs {
+            x: u128::MAX / 3,
+            y: 1_000_000_000_000_000_000,
+            denominator: 1_000_000_000_000_000_001,
+            fee_rate: 3_000_000_000_000_000,
+            scale: 1_000_000_000_000_000_000,
+            shift: 60,
+        },
+    );
+    report(
+        &env,
+        "I256",
+        &Inputs {
+            x: I256::from_i128(&env, i128::MAX / 3),
+            y: I256::from_i128(&env, 1_000_000_000_000_000_000),
+            denominator: I256::from_i128(&env, 1_000_000_000_000_000_001),
+            fee_rate: I256::from_i128(&env, 3_000_000_000_000_000),
+            scale: I256::from_i128(&env, 1_000_000_000_000_000_000),
+            shift: 60,
+        },
+    );
+    report(
+        &env,
+        "U256",
+        &Inputs {
+            x: U256::from_u128(&env, u128::MAX / 3),
+            y: U256::from_u128(&env, 1_000_000_000_000_000_000),
+            denominator: U256::from_u128(&env, 1_000_000_000_000_000_001),
+            fee_rate: U256::from_u128(&env, 3_000_000_000_000_000),
+            scale: U256::from_u128(&env, 1_000_000_000_000_000_000),
+            shift: 60,
+        },
+    );
+}
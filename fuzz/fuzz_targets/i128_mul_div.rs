@@ -0,0 +1,46 @@
+//! `FixedPoint` for i128 and u128, which returns None on phantom overflow.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use num_bigint::BigInt;
+use soroban_fixed_point_math::FixedPoint;
+use soroban_fixed_point_math_fuzz::{assert_rounded, fits};
+
+fuzz_target!(|input: (i128, i128, i128, u128, u128, u128)| {
+    let (x, y, z, ux, uy, uz) = input;
+
+    let product = BigInt::from(x) * y;
+    assert_rounded(
+        &product,
+        &BigInt::from(z),
+        fits::<i128>(&product),
+        x.fixed_mul_floor(y, z),
+        x.fixed_mul_ceil(y, z),
+    );
+    let product = BigInt::from(x) * z;
+    assert_rounded(
+        &product,
+        &BigInt::from(y),
+        fits::<i128>(&product),
+        x.fixed_div_floor(y, z),
+        x.fixed_div_ceil(y, z),
+    );
+
+    let product = BigInt::from(ux) * uy;
+    assert_rounded(
+        &product,
+        &BigInt::from(uz),
+        fits::<u128>(&product),
+        ux.fixed_mul_floor(uy, uz),
+        ux.fixed_mul_ceil(uy, uz),
+    );
+    let product = BigInt::from(ux) * uz;
+    assert_rounded(
+        &product,
+        &BigInt::from(uy),
+        fits::<u128>(&product),
+        ux.fixed_div_floor(uy, uz),
+        ux.fixed_div_ceil(uy, uz),
+    );
+});
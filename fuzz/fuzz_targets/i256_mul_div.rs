@@ -0,0 +1,44 @@
+//! `SorobanFixedPoint` for I256. The host panics if the product or result overflows, so only
+//! inputs with a representable product and result are computed.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::Zero;
+use soroban_fixed_point_math::SorobanFixedPoint;
+use soroban_fixed_point_math_fuzz::{assert_bounds, fits_i256, from_i256, to_i256};
+use soroban_sdk::Env;
+
+/// Asserts the floor and ceil of (x * y) / d are computed, if representable
+fn check(env: &Env, x: &BigInt, y: &BigInt, d: &BigInt) {
+    let n = x * y;
+    if d.is_zero() || !fits_i256(&n) || !fits_i256(&n.div_floor(d)) || !fits_i256(&n.div_ceil(d)) {
+        return;
+    }
+    let (hx, hy, hd) = (to_i256(env, x), to_i256(env, y), to_i256(env, d));
+
+    let floor = from_i256(&hx.fixed_mul_floor(env, &hy, &hd));
+    let ceil = from_i256(&hx.fixed_mul_ceil(env, &hy, &hd));
+    assert_eq!(floor, n.div_floor(d));
+    assert_eq!(ceil, n.div_ceil(d));
+    assert_bounds(&n, d, &floor, &ceil);
+
+    // fixed_div(x, d, y) computes the same quotient as fixed_mul(x, y, d)
+    assert_eq!(from_i256(&hx.fixed_div_floor(env, &hd, &hy)), floor);
+    assert_eq!(from_i256(&hx.fixed_div_ceil(env, &hd, &hy)), ceil);
+}
+
+// each operand is shifted right by a fuzzed amount, so values of every width are reached
+fuzz_target!(|input: ([u8; 32], u8, [u8; 32], u8, [u8; 32], u8)| {
+    let (x, x_shift, y, y_shift, d, d_shift) = input;
+    let env = Env::default();
+
+    check(
+        &env,
+        &(BigInt::from_signed_bytes_be(&x) >> x_shift),
+        &(BigInt::from_signed_bytes_be(&y) >> y_shift),
+        &(BigInt::from_signed_bytes_be(&d) >> d_shift),
+    );
+});
@@ -0,0 +1,44 @@
+//! `FixedPoint` for i64 and u64, including the fallback to 128 bit math on phantom overflow.
+//! The product of two 64 bit values always fits in 128 bits, so every representable result is
+//! expected.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use num_bigint::BigInt;
+use soroban_fixed_point_math::FixedPoint;
+use soroban_fixed_point_math_fuzz::assert_rounded;
+
+fuzz_target!(|input: (i64, i64, i64, u64, u64, u64)| {
+    let (x, y, z, ux, uy, uz) = input;
+
+    assert_rounded(
+        &(BigInt::from(x) * y),
+        &BigInt::from(z),
+        true,
+        x.fixed_mul_floor(y, z),
+        x.fixed_mul_ceil(y, z),
+    );
+    assert_rounded(
+        &(BigInt::from(x) * z),
+        &BigInt::from(y),
+        true,
+        x.fixed_div_floor(y, z),
+        x.fixed_div_ceil(y, z),
+    );
+
+    assert_rounded(
+        &(BigInt::from(ux) * uy),
+        &BigInt::from(uz),
+        true,
+        ux.fixed_mul_floor(uy, uz),
+        ux.fixed_mul_ceil(uy, uz),
+    );
+    assert_rounded(
+        &(BigInt::from(ux) * uz),
+        &BigInt::from(uy),
+        true,
+        ux.fixed_div_floor(uy, uz),
+        ux.fixed_div_ceil(uy, uz),
+    );
+});
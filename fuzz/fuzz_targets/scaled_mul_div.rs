@@ -0,0 +1,63 @@
+//! `SorobanFixedPoint` for i128 and u128, which scales to I256 and U256 on phantom overflow.
+//! The operations panic if the result is not representable, so only representable results are
+//! computed.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::Zero;
+use soroban_fixed_point_math::SorobanFixedPoint;
+use soroban_fixed_point_math_fuzz::{assert_bounds, fits};
+use soroban_sdk::Env;
+
+/// Asserts the floor and ceil of n / d are computed, if both are representable by T
+fn check<T>(n: BigInt, d: BigInt, floor: impl FnOnce() -> T, ceil: impl FnOnce() -> T)
+where
+    T: Into<BigInt> + TryFrom<BigInt>,
+{
+    if d.is_zero() {
+        return;
+    }
+    let expected_floor = n.div_floor(&d);
+    let expected_ceil = n.div_ceil(&d);
+    if !fits::<T>(&expected_floor) || !fits::<T>(&expected_ceil) {
+        return;
+    }
+    let (floor, ceil) = (floor().into(), ceil().into());
+    assert_eq!(floor, expected_floor);
+    assert_eq!(ceil, expected_ceil);
+    assert_bounds(&n, &d, &floor, &ceil);
+}
+
+fuzz_target!(|input: (i128, i128, i128, u128, u128, u128)| {
+    let (x, y, z, ux, uy, uz) = input;
+    let env = Env::default();
+
+    check(
+        BigInt::from(x) * y,
+        BigInt::from(z),
+        || SorobanFixedPoint::fixed_mul_floor(&x, &env, &y, &z),
+        || SorobanFixedPoint::fixed_mul_ceil(&x, &env, &y, &z),
+    );
+    check(
+        BigInt::from(x) * z,
+        BigInt::from(y),
+        || SorobanFixedPoint::fixed_div_floor(&x, &env, &y, &z),
+        || SorobanFixedPoint::fixed_div_ceil(&x, &env, &y, &z),
+    );
+
+    check(
+        BigInt::from(ux) * uy,
+        BigInt::from(uz),
+        || SorobanFixedPoint::fixed_mul_floor(&ux, &env, &uy, &uz),
+        || SorobanFixedPoint::fixed_mul_ceil(&ux, &env, &uy, &uz),
+    );
+    check(
+        BigInt::from(ux) * uz,
+        BigInt::from(uy),
+        || SorobanFixedPoint::fixed_div_floor(&ux, &env, &uy, &uz),
+        || SorobanFixedPoint::fixed_div_ceil(&ux, &env, &uy, &uz),
+    );
+});
@@ -0,0 +1,6 @@
+//! Reference model and conversions shared by the fuzz targets, from the property tests.
+
+#[path = "../../tests/common/mod.rs"]
+mod common;
+
+pub use common::*;
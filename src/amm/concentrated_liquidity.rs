@@ -0,0 +1,560 @@
+use soroban_sdk::{unwrap::UnwrapOptimized, Env, I256, U256};
+
+use crate::{
+    i256,
+    u256::{bit_length, div_ceil, mul_div_floor, mul_div_full_ceil, mul_div_full_floor},
+    Rounding,
+};
+
+/// The minimum tick, such that 1.0001^MIN_TICK is the minimum price
+pub const MIN_TICK: i32 = -887272;
+
+/// The maximum tick, such that 1.0001^MAX_TICK is the maximum price
+pub const MAX_TICK: i32 = 887272;
+
+/// The number of fractional bits in a Q64.96 sqrt price
+pub const RESOLUTION: u32 = 96;
+
+/// sqrt(1.0001)^-(2^i) as Q128.128 numbers, for each bit i of a tick
+const TICK_RATIOS: [u128; 20] = [
+    0xfffcb933bd6fad37aa2d162d1a594001,
+    0xfff97272373d413259a46990580e213a,
+    0xfff2e50f5f656932ef12357cf3c7fdcc,
+    0xffe5caca7e10e4e61c3624eaa0941cd0,
+    0xffcb9843d60f6159c9db58835c926644,
+    0xff973b41fa98c081472e6896dfb254c0,
+    0xff2ea16466c96a3843ec78b326b52861,
+    0xfe5dee046a99a2a811c461f1969c3053,
+    0xfcbe86c7900a88aedcffc83b479aa3a4,
+    0xf987a7253ac413176f2b074cf7815e54,
+    0xf3392b0822b70005940c7a398e4b70f3,
+    0xe7159475a2c29b7443b29c7fa6e889d9,
+    0xd097f3bdfd2022b8845ad8f792aa5825,
+    0xa9f746462d870fdf8a65dc1f90e061e5,
+    0x70d869a156d2a1b890bb3df62baf32f7,
+    0x31be135f97d08fd981231505542fcfa6,
+    0x9aa508b5b7a84e1c677de54f3e99bc9,
+    0x5d6af8dedb81196699c329225ee604,
+    0x2216e584f5fa1ea926041bedfe98,
+    0x48a170391f7dc42444e8fa2,
+];
+
+// @dev - sqrt prices are Q64.96 binary fixed point numbers, sqrt(price) * 2^96, matching Uniswap V3. The tick
+// math is a port of Uniswap V3's TickMath, and produces identical results.
+// More detail can be found here: https://github.com/Uniswap/v3-core/blob/main/contracts/libraries/TickMath.sol
+//
+// Like Uniswap V3's SqrtPriceMath, amount deltas and the next sqrt price compute intermediate products with
+// 512 bits where Uniswap uses FullMath, so they produce identical results and only panic if a result exceeds
+// 256 bits.
+
+/// Returns the minimum sqrt price, at `MIN_TICK`
+pub fn min_sqrt_price(env: &Env) -> U256 {
+    U256::from_u128(env, 4295128739)
+}
+
+/// Returns the maximum sqrt price, at `MAX_TICK`
+pub fn max_sqrt_price(env: &Env) -> U256 {
+    U256::from_parts(env, 0, 0xfffd8963, 0xefd1fc6a50648849, 0x5d951d5263988d26)
+}
+
+/// Calculates sqrt(1.0001^tick) * 2^96, rounding up.
+///
+/// ### Panics
+/// If the tick is not within [MIN_TICK, MAX_TICK]
+pub fn get_sqrt_price_at_tick(env: &Env, tick: i32) -> U256 {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        panic!("tick out of bounds");
+    }
+    let abs_tick = tick.unsigned_abs();
+
+    // calculate sqrt(1.0001)^-abs_tick as a Q128.128 number
+    let mut ratio = if abs_tick & 1 != 0 {
+        U256::from_u128(env, TICK_RATIOS[0])
+    } else {
+        U256::from_u32(env, 1).shl(128)
+    };
+    for (i, tick_ratio) in TICK_RATIOS.iter().enumerate().skip(1) {
+        if abs_tick & (1 << i) != 0 {
+            ratio = ratio.mul(&U256::from_u128(env, *tick_ratio)).shr(128);
+        }
+    }
+    if tick > 0 {
+        ratio = u256_max(env).div(&ratio);
+    }
+
+    // convert from Q128.128 to Q64.96, rounding up
+    let remainder = ratio.rem_euclid(&U256::from_u32(env, 1).shl(32));
+    let sqrt_price = ratio.shr(32);
+    if remainder == U256::from_u32(env, 0) {
+        sqrt_price
+    } else {
+        sqrt_price.add(&U256::from_u32(env, 1))
+    }
+}
+
+/// Calculates the greatest tick such that `get_sqrt_price_at_tick(tick) <= sqrt_price`.
+///
+/// ### Panics
+/// If the sqrt price is not within [min_sqrt_price, max_sqrt_price)
+pub fn get_tick_at_sqrt_price(env: &Env, sqrt_price: &U256) -> i32 {
+    if *sqrt_price < min_sqrt_price(env) || *sqrt_price >= max_sqrt_price(env) {
+        panic!("sqrt price out of bounds");
+    }
+    // calculate log2 of the price as a Q64.64 number
+    let ratio = sqrt_price.shl(32);
+    let msb = bit_length(env, &ratio) - 1;
+    let mut r = if msb >= 128 {
+        ratio.shr(msb - 127)
+    } else {
+        ratio.shl(127 - msb)
+    };
+    let mut log_2: i128 = (msb as i128 - 128) << 64;
+    let one = U256::from_u32(env, 1);
+    for i in 0..14 {
+        r = r.mul(&r).shr(127);
+        if r.shr(128) == one {
+            log_2 |= 1 << (63 - i);
+            r = r.shr(1);
+        }
+    }
+
+    // convert to log_sqrt(1.0001) of the price, and find the tick range the error bounds allow
+    let log_sqrt_10001 =
+        i256::from_i128(env, log_2).mul(&i256::from_i128(env, 255738958999603826347141));
+    let tick_low = log_sqrt_10001
+        .sub(&i256::from_i128(env, 3402992956809132418596140100660247210))
+        .shr(128);
+    let tick_low = i256::to_i128(&tick_low).unwrap_optimized() as i32;
+    let tick_high = log_sqrt_10001
+        .add(&I256::from_parts(
+            env,
+            0,
+            0,
+            0xdb2df09e81959a81,
+            0x455e260799a0632f,
+        ))
+        .shr(128);
+    let tick_high = i256::to_i128(&tick_high).unwrap_optimized() as i32;
+    if tick_low == tick_high || get_sqrt_price_at_tick(env, tick_high) > *sqrt_price {
+        tick_low
+    } else {
+        tick_high
+    }
+}
+
+/// Calculates the amount of token0 between two sqrt prices for a given liquidity, or
+/// liquidity * (sqrt_price_b - sqrt_price_a) / (sqrt_price_a * sqrt_price_b).
+///
+/// Round up for amounts paid to the pool, and down for amounts paid by the pool.
+///
+/// ### Panics
+/// If either sqrt price is 0
+pub fn get_amount0_delta(
+    env: &Env,
+    sqrt_price_a: &U256,
+    sqrt_price_b: &U256,
+    liquidity: u128,
+    rounding: Rounding,
+) -> U256 {
+    let (lower, upper) = sort(sqrt_price_a, sqrt_price_b);
+    let numerator_1 = U256::from_u128(env, liquidity).shl(RESOLUTION);
+    let numerator_2 = upper.sub(lower);
+    match rounding {
+        Rounding::Floor => mul_div_full_floor(env, &numerator_1, &numerator_2, upper).div(lower),
+        Rounding::Ceil => {
+            let amount = mul_div_full_ceil(env, &numerator_1, &numerator_2, upper);
+            div_ceil(env, &amount, lower)
+        }
+    }
+}
+
+/// Calculates the amount of token1 between two sqrt prices for a given liquidity, or
+/// liquidity * (sqrt_price_b - sqrt_price_a).
+///
+/// Round up for amounts paid to the pool, and down for amounts paid by the pool.
+///
+/// ### Panics
+/// If the result exceeds 256 bits
+pub fn get_amount1_delta(
+    env: &Env,
+    sqrt_price_a: &U256,
+    sqrt_price_b: &U256,
+    liquidity: u128,
+    rounding: Rounding,
+) -> U256 {
+    let (lower, upper) = sort(sqrt_price_a, sqrt_price_b);
+    let liquidity = U256::from_u128(env, liquidity);
+    let q96 = U256::from_u32(env, 1).shl(RESOLUTION);
+    match rounding {
+        Rounding::Floor => mul_div_full_floor(env, &liquidity, &upper.sub(lower), &q96),
+        Rounding::Ceil => mul_div_full_ceil(env, &liquidity, &upper.sub(lower), &q96),
+    }
+}
+
+/// Calculates the next sqrt price after `amount_in` of token0 (if `zero_for_one`) or token1 is
+/// swapped into the pool at the current `sqrt_price` and `liquidity`.
+///
+/// The result is rounded such that the pool never receives less than `amount_in` would require.
+///
+/// ### Panics
+/// If the sqrt price or liquidity is 0, or an overflow occurs
+pub fn get_next_sqrt_price_from_input(
+    env: &Env,
+    sqrt_price: &U256,
+    liquidity: u128,
+    amount_in: &U256,
+    zero_for_one: bool,
+) -> U256 {
+    let zero = U256::from_u32(env, 0);
+    if *sqrt_price == zero || liquidity == 0 {
+        panic!("invalid sqrt price or liquidity");
+    }
+    if *amount_in == zero {
+        return sqrt_price.clone();
+    }
+    if zero_for_one {
+        // the price decreases, so round the next price up
+        let numerator_1 = U256::from_u128(env, liquidity).shl(RESOLUTION);
+        let max = u256_max(env);
+        if *amount_in <= max.div(sqrt_price) {
+            let product = amount_in.mul(sqrt_price);
+            if product <= max.sub(&numerator_1) {
+                // liquidity * sqrt_price / (liquidity + amount_in * sqrt_price), with a 512 bit
+                // intermediate product like Uniswap's `FullMath.mulDivRoundingUp`
+                let denominator = numerator_1.add(&product);
+                return mul_div_full_ceil(env, &numerator_1, sqrt_price, &denominator);
+            }
+        }
+        // liquidity / (liquidity / sqrt_price + amount_in), which is less precise but cannot overflow
+        let denominator = numerator_1.div(sqrt_price).add(amount_in);
+        div_ceil(env, &numerator_1, &denominator)
+    } else {
+        // the price increases, so round the next price down
+        let q96 = U256::from_u32(env, 1).shl(RESOLUTION);
+        let liquidity = U256::from_u128(env, liquidity);
+        // amount_in * 2^96 only fits in a U256 if amount_in fits in 160 bits
+        let quotient = if bit_length(env, amount_in) <= 160 {
+            mul_div_floor(amount_in, &q96, &liquidity)
+        } else {
+            mul_div_full_floor(env, amount_in, &q96, &liquidity)
+        };
+        sqrt_price.add(&quotient)
+    }
+}
+
+/// Returns (min(a, b), max(a, b))
+fn sort<'a>(a: &'a U256, b: &'a U256) -> (&'a U256, &'a U256) {
+    if a > b {
+        (b, a)
+    } else {
+        (a, b)
+    }
+}
+
+/// Returns 2^256 - 1
+fn u256_max(env: &Env) -> U256 {
+    U256::from_parts(env, u64::MAX, u64::MAX, u64::MAX, u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn q96(env: &Env) -> U256 {
+        U256::from_u32(env, 1).shl(RESOLUTION)
+    }
+
+    /// sqrt(1.21) * 2^96
+    fn sqrt_price_121_100(env: &Env) -> U256 {
+        U256::from_u128(env, 87150978765690771352898345369)
+    }
+
+    /********** get_sqrt_price_at_tick **********/
+
+    #[test]
+    fn test_get_sqrt_price_at_tick_bounds() {
+        let env = Env::default();
+
+        assert_eq!(get_sqrt_price_at_tick(&env, MIN_TICK), min_sqrt_price(&env));
+        assert_eq!(get_sqrt_price_at_tick(&env, MAX_TICK), max_sqrt_price(&env));
+        assert_eq!(get_sqrt_price_at_tick(&env, 0), q96(&env));
+    }
+
+    #[test]
+    fn test_get_sqrt_price_at_tick() {
+        let env = Env::default();
+
+        assert_eq!(
+            get_sqrt_price_at_tick(&env, 50),
+            U256::from_u128(&env, 79426470787362580746886972461)
+        );
+        assert_eq!(
+            get_sqrt_price_at_tick(&env, -50),
+            U256::from_u128(&env, 79030349367926598376800521322)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "tick out of bounds")]
+    fn test_get_sqrt_price_at_tick_out_of_bounds() {
+        let env = Env::default();
+
+        get_sqrt_price_at_tick(&env, MAX_TICK + 1);
+    }
+
+    /********** get_tick_at_sqrt_price **********/
+
+    #[test]
+    fn test_get_tick_at_sqrt_price_bounds() {
+        let env = Env::default();
+        let one = U256::from_u32(&env, 1);
+
+        assert_eq!(
+            get_tick_at_sqrt_price(&env, &min_sqrt_price(&env)),
+            MIN_TICK
+        );
+        assert_eq!(
+            get_tick_at_sqrt_price(&env, &max_sqrt_price(&env).sub(&one)),
+            MAX_TICK - 1
+        );
+    }
+
+    #[test]
+    fn test_get_tick_at_sqrt_price_round_trip() {
+        let env = Env::default();
+        let one = U256::from_u32(&env, 1);
+
+        for tick in [-500_000, -1234, -1, 0, 1, 50, 98_765, 500_000] {
+            let sqrt_price = get_sqrt_price_at_tick(&env, tick);
+            assert_eq!(get_tick_at_sqrt_price(&env, &sqrt_price), tick);
+            assert_eq!(
+                get_tick_at_sqrt_price(&env, &sqrt_price.sub(&one)),
+                tick - 1
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "sqrt price out of bounds")]
+    fn test_get_tick_at_sqrt_price_out_of_bounds() {
+        let env = Env::default();
+
+        get_tick_at_sqrt_price(&env, &max_sqrt_price(&env));
+    }
+
+    /********** get_amount0_delta **********/
+
+    #[test]
+    fn test_get_amount0_delta_rounding() {
+        let env = Env::default();
+        let liquidity: u128 = 10u128.pow(18);
+
+        let ceil = get_amount0_delta(
+            &env,
+            &q96(&env),
+            &sqrt_price_121_100(&env),
+            liquidity,
+            Rounding::Ceil,
+        );
+        let floor = get_amount0_delta(
+            &env,
+            &sqrt_price_121_100(&env),
+            &q96(&env),
+            liquidity,
+            Rounding::Floor,
+        );
+
+        assert_eq!(ceil, U256::from_u128(&env, 90909090909090910));
+        assert_eq!(floor, U256::from_u128(&env, 90909090909090909));
+    }
+
+    #[test]
+    fn test_get_amount0_delta_equal_prices() {
+        let env = Env::default();
+
+        let result =
+            get_amount0_delta(&env, &q96(&env), &q96(&env), 10u128.pow(18), Rounding::Ceil);
+
+        assert_eq!(result, U256::from_u32(&env, 0));
+    }
+
+    #[test]
+    fn test_get_amount0_delta_full_range() {
+        let env = Env::default();
+        let liquidity: u128 = 10u128.pow(18);
+        let lower = get_sqrt_price_at_tick(&env, MIN_TICK);
+        let upper = get_sqrt_price_at_tick(&env, MAX_TICK);
+
+        let ceil = get_amount0_delta(&env, &lower, &upper, liquidity, Rounding::Ceil);
+        let floor = get_amount0_delta(&env, &lower, &upper, liquidity, Rounding::Floor);
+
+        assert_eq!(
+            ceil,
+            U256::from_u128(&env, 18446050707367246063248664439159200718)
+        );
+        assert_eq!(
+            floor,
+            U256::from_u128(&env, 18446050707367246063248664439159200717)
+        );
+    }
+
+    #[test]
+    fn test_get_amount0_delta_wide_range() {
+        let env = Env::default();
+        let liquidity: u128 = 10u128.pow(18);
+        let upper = get_sqrt_price_at_tick(&env, 600000);
+
+        let ceil = get_amount0_delta(&env, &q96(&env), &upper, liquidity, Rounding::Ceil);
+        let floor = get_amount0_delta(&env, &q96(&env), &upper, liquidity, Rounding::Floor);
+
+        assert_eq!(ceil, U256::from_u128(&env, 999999999999906284));
+        assert_eq!(floor, U256::from_u128(&env, 999999999999906283));
+    }
+
+    /********** get_amount1_delta **********/
+
+    #[test]
+    fn test_get_amount1_delta_rounding() {
+        let env = Env::default();
+        let liquidity: u128 = 10u128.pow(18);
+
+        let ceil = get_amount1_delta(
+            &env,
+            &q96(&env),
+            &sqrt_price_121_100(&env),
+            liquidity,
+            Rounding::Ceil,
+        );
+        let floor = get_amount1_delta(
+            &env,
+            &q96(&env),
+            &sqrt_price_121_100(&env),
+            liquidity,
+            Rounding::Floor,
+        );
+
+        assert_eq!(ceil, U256::from_u128(&env, 100000000000000000));
+        assert_eq!(floor, U256::from_u128(&env, 99999999999999999));
+    }
+
+    #[test]
+    fn test_get_amount1_delta_full_range() {
+        let env = Env::default();
+        let liquidity: u128 = 10u128.pow(18);
+        let lower = get_sqrt_price_at_tick(&env, MIN_TICK);
+        let upper = get_sqrt_price_at_tick(&env, MAX_TICK);
+
+        let ceil = get_amount1_delta(&env, &lower, &upper, liquidity, Rounding::Ceil);
+        let floor = get_amount1_delta(&env, &lower, &upper, liquidity, Rounding::Floor);
+
+        assert_eq!(
+            ceil,
+            U256::from_u128(&env, 18446050711097703530314034002219689209)
+        );
+        assert_eq!(
+            floor,
+            U256::from_u128(&env, 18446050711097703530314034002219689208)
+        );
+    }
+
+    /********** get_next_sqrt_price_from_input **********/
+
+    #[test]
+    fn test_get_next_sqrt_price_from_input_token1() {
+        let env = Env::default();
+        let amount_in = U256::from_u128(&env, 10u128.pow(17));
+
+        let result =
+            get_next_sqrt_price_from_input(&env, &q96(&env), 10u128.pow(18), &amount_in, false);
+
+        assert_eq!(result, sqrt_price_121_100(&env));
+    }
+
+    #[test]
+    fn test_get_next_sqrt_price_from_input_token0() {
+        let env = Env::default();
+        let amount_in = U256::from_u128(&env, 10u128.pow(17));
+
+        let result =
+            get_next_sqrt_price_from_input(&env, &q96(&env), 10u128.pow(18), &amount_in, true);
+
+        assert_eq!(result, U256::from_u128(&env, 72025602285694852357767227579));
+    }
+
+    #[test]
+    fn test_get_next_sqrt_price_from_input_token0_large_amount() {
+        let env = Env::default();
+        let amount_in = U256::from_parts(&env, 0, u64::MAX, u64::MAX, u64::MAX);
+
+        let result = get_next_sqrt_price_from_input(
+            &env,
+            &max_sqrt_price(&env),
+            u128::MAX,
+            &amount_in,
+            true,
+        );
+
+        // amount_in * sqrt_price overflows, so liquidity / (liquidity / sqrt_price + amount_in) is used
+        assert_eq!(result, U256::from_u128(&env, 4294967296));
+    }
+
+    #[test]
+    fn test_get_next_sqrt_price_from_input_token0_large_liquidity() {
+        let env = Env::default();
+        let amount_in = U256::from_u128(&env, 10u128.pow(18));
+
+        let result = get_next_sqrt_price_from_input(
+            &env,
+            &sqrt_price_121_100(&env),
+            10u128.pow(24),
+            &amount_in,
+            true,
+        );
+
+        // liquidity * 2^96 * sqrt_price overflows 256 bits, but Uniswap's
+        // getNextSqrtPriceFromAmount0RoundingUp still uses the exact formula
+        assert_eq!(result, U256::from_u128(&env, 87150882899719581661358517875));
+    }
+
+    #[test]
+    fn test_get_next_sqrt_price_from_input_token1_large_amount() {
+        let env = Env::default();
+        // 2^200 + 12345
+        let amount_in = U256::from_parts(&env, 256, 0, 0, 12345);
+
+        let result = get_next_sqrt_price_from_input(
+            &env,
+            &sqrt_price_121_100(&env),
+            10u128.pow(24),
+            &amount_in,
+            false,
+        );
+
+        // amount_in * 2^96 overflows 256 bits, so Uniswap's getNextSqrtPriceFromAmount1RoundingDown
+        // uses `FullMath.mulDiv`
+        // sqrt_price + floor(amount_in * 2^96 / liquidity)
+        let expected = U256::from_parts(
+            &env,
+            20282409,
+            11135407873977820373,
+            3234976976406622725,
+            14211123621887881967,
+        );
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_get_next_sqrt_price_from_input_zero_amount() {
+        let env = Env::default();
+
+        let result = get_next_sqrt_price_from_input(
+            &env,
+            &q96(&env),
+            10u128.pow(18),
+            &U256::from_u32(&env, 0),
+            true,
+        );
+
+        assert_eq!(result, q96(&env));
+    }
+}
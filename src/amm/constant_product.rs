@@ -0,0 +1,309 @@
+use soroban_sdk::{unwrap::UnwrapOptimized, Env};
+
+use crate::{amm::BPS_SCALAR, fixed_point::fee_complement, i256, SorobanFixedPoint};
+
+// @dev - math for a constant product (x * y = k) pool. Every function rounds in favor of the pool,
+// such that the invariant k never decreases due to rounding.
+//
+// Calculations use `SorobanFixedPoint` so large reserves do not cause a phantom overflow. Swap amounts scaled
+// by `BPS_SCALAR` fall back to I256 math if they do not fit in an i128. All functions panic if an arithmetic
+// error occurs, or if called with a fee of 100% or more.
+
+/// Calculates the amount of the output token received for depositing `amount_in` of the input token,
+/// after a fee of `fee_bps` is taken from the input. Rounds down.
+///
+/// ### Panics
+/// If any reserve is 0, the fee is not within [0, 10000), or an overflow occurs
+pub fn get_amount_out(
+    env: &Env,
+    amount_in: i128,
+    reserve_in: i128,
+    reserve_out: i128,
+    fee_bps: u32,
+) -> i128 {
+    if amount_in <= 0 || reserve_in <= 0 || reserve_out <= 0 {
+        panic!("invalid amount or reserves");
+    }
+    let fee_complement = fee_complement(fee_bps as i128, BPS_SCALAR).unwrap_optimized();
+    let amount_in_with_fee = amount_in.checked_mul(fee_complement);
+    let denominator =
+        amount_in_with_fee.and_then(|a| reserve_in.checked_mul(BPS_SCALAR)?.checked_add(a));
+    match (amount_in_with_fee, denominator) {
+        (Some(a), Some(d)) => a.fixed_mul_floor(env, &reserve_out, &d),
+        _ => {
+            let a = i256::from_i128(env, amount_in).mul(&i256::from_i128(env, fee_complement));
+            let d = i256::from_i128(env, reserve_in)
+                .mul(&i256::from_i128(env, BPS_SCALAR))
+                .add(&a);
+            let result = a.fixed_mul_floor(env, &i256::from_i128(env, reserve_out), &d);
+            i256::to_i128(&result).unwrap_optimized()
+        }
+    }
+}
+
+/// Calculates the amount of the input token required to receive `amount_out` of the output token,
+/// after a fee of `fee_bps` is taken from the input. Rounds up.
+///
+/// ### Panics
+/// If any reserve is 0, `amount_out` is not less than `reserve_out`, the fee is not within
+/// [0, 10000), or an overflow occurs
+pub fn get_amount_in(
+    env: &Env,
+    amount_out: i128,
+    reserve_in: i128,
+    reserve_out: i128,
+    fee_bps: u32,
+) -> i128 {
+    if amount_out <= 0 || reserve_in <= 0 || amount_out >= reserve_out {
+        panic!("invalid amount or reserves");
+    }
+    let fee_complement = fee_complement(fee_bps as i128, BPS_SCALAR).unwrap_optimized();
+    let numerator = amount_out.checked_mul(BPS_SCALAR);
+    let denominator = (reserve_out - amount_out).checked_mul(fee_complement);
+    match (numerator, denominator) {
+        (Some(n), Some(d)) => n.fixed_mul_ceil(env, &reserve_in, &d),
+        _ => {
+            let n = i256::from_i128(env, amount_out).mul(&i256::from_i128(env, BPS_SCALAR));
+            let d = i256::from_i128(env, reserve_out - amount_out)
+                .mul(&i256::from_i128(env, fee_complement));
+            let result = n.fixed_mul_ceil(env, &i256::from_i128(env, reserve_in), &d);
+            i256::to_i128(&result).unwrap_optimized()
+        }
+    }
+}
+
+/// Calculates the amount of token b with equal value to `amount_a` of token a at the current
+/// reserves, without a fee. Rounds down.
+///
+/// ### Panics
+/// If any reserve is 0 or an overflow occurs
+pub fn quote(env: &Env, amount_a: i128, reserve_a: i128, reserve_b: i128) -> i128 {
+    if amount_a < 0 || reserve_a <= 0 || reserve_b <= 0 {
+        panic!("invalid amount or reserves");
+    }
+    amount_a.fixed_mul_floor(env, &reserve_b, &reserve_a)
+}
+
+/// Calculates the LP tokens minted for depositing `amount_a` and `amount_b` into a pool with
+/// `total_supply` LP tokens outstanding. Rounds down.
+///
+/// For the initial deposit, the LP tokens minted are sqrt(amount_a * amount_b). The caller is
+/// responsible for locking any minimum liquidity. Otherwise, the LP tokens minted are based on
+/// the least valuable side of the deposit, so any excess of the other token is donated to the pool.
+///
+/// ### Panics
+/// If any amount is negative, any reserve is 0 when `total_supply` is not, or an overflow occurs
+pub fn get_liquidity_minted(
+    env: &Env,
+    amount_a: i128,
+    amount_b: i128,
+    reserve_a: i128,
+    reserve_b: i128,
+    total_supply: i128,
+) -> i128 {
+    if amount_a < 0 || amount_b < 0 || total_supply < 0 {
+        panic!("invalid amounts");
+    }
+    if total_supply == 0 {
+        let liquidity = crate::u128::scaled_sqrt_mul(env, amount_a as u128, amount_b as u128);
+        // the root of the product of two i128 values always fits in an i128
+        return liquidity as i128;
+    }
+    let liquidity_a = amount_a.fixed_mul_floor(env, &total_supply, &reserve_a);
+    let liquidity_b = amount_b.fixed_mul_floor(env, &total_supply, &reserve_b);
+    liquidity_a.min(liquidity_b)
+}
+
+/// Calculates the amounts of token a and token b returned for burning `liquidity` LP tokens from
+/// a pool with `total_supply` LP tokens outstanding. Rounds down.
+///
+/// Returns (amount_a, amount_b)
+///
+/// ### Panics
+/// If `liquidity` is not within [0, total_supply] or an overflow occurs
+pub fn get_amounts_burned(
+    env: &Env,
+    liquidity: i128,
+    reserve_a: i128,
+    reserve_b: i128,
+    total_supply: i128,
+) -> (i128, i128) {
+    if liquidity < 0 || liquidity > total_supply {
+        panic!("invalid liquidity");
+    }
+    (
+        liquidity.fixed_mul_floor(env, &reserve_a, &total_supply),
+        liquidity.fixed_mul_floor(env, &reserve_b, &total_supply),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /********** get_amount_out **********/
+
+    #[test]
+    fn test_get_amount_out_rounds_down() {
+        let env = Env::default();
+
+        let result = get_amount_out(&env, 1_0000000, 100_0000000, 200_0000000, 30);
+
+        // 1 * 0.997 * 200 / (100 + 0.997) = 1.974316...
+        assert_eq!(result, 1_9743160);
+    }
+
+    #[test]
+    fn test_get_amount_out_no_fee() {
+        let env = Env::default();
+
+        let result = get_amount_out(&env, 100_0000000, 100_0000000, 200_0000000, 0);
+
+        assert_eq!(result, 100_0000000);
+    }
+
+    #[test]
+    fn test_get_amount_out_large_reserves_scales() {
+        let env = Env::default();
+        let reserve: i128 = 10i128.pow(33);
+
+        let result = get_amount_out(&env, 10i128.pow(30), reserve, reserve, 0);
+
+        // 1e30 * 1e33 / (1e33 + 1e30) = 999000999000999000999000999000.999...
+        assert_eq!(result, 999_000_999_000_999_000_999_000_999_000);
+    }
+
+    #[test]
+    fn test_get_amount_out_reserves_over_bps_limit() {
+        let env = Env::default();
+        let reserve: i128 = 10i128.pow(36);
+
+        let result = get_amount_out(&env, 10i128.pow(33), reserve, 2 * reserve, 30);
+
+        // 1e33 * 0.997 * 2e36 / (1e36 + 1e33 * 0.997) = 1992013962079806432986312646291647.3...
+        assert_eq!(result, 1_992_013_962_079_806_432_986_312_646_291_647);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_get_amount_out_invalid_fee() {
+        let env = Env::default();
+
+        get_amount_out(&env, 1_0000000, 100_0000000, 200_0000000, 10_000);
+    }
+
+    /********** get_amount_in **********/
+
+    #[test]
+    fn test_get_amount_in_rounds_up() {
+        let env = Env::default();
+
+        let result = get_amount_in(&env, 1_9743160, 100_0000000, 200_0000000, 30);
+
+        assert_eq!(result, 1_0000000);
+        assert!(get_amount_out(&env, result, 100_0000000, 200_0000000, 30) >= 1_9743160);
+    }
+
+    #[test]
+    fn test_get_amount_in_is_minimal() {
+        let env = Env::default();
+
+        let result = get_amount_in(&env, 3_3333333, 100_0000000, 200_0000000, 30);
+
+        assert!(get_amount_out(&env, result, 100_0000000, 200_0000000, 30) >= 3_3333333);
+        assert!(get_amount_out(&env, result - 1, 100_0000000, 200_0000000, 30) < 3_3333333);
+    }
+
+    #[test]
+    fn test_get_amount_in_reserves_over_bps_limit() {
+        let env = Env::default();
+        let reserve: i128 = 10i128.pow(36);
+
+        let result = get_amount_in(
+            &env,
+            1_992_013_962_079_806_432_986_312_646_291_647,
+            reserve,
+            2 * reserve,
+            30,
+        );
+
+        assert_eq!(result, 10i128.pow(33));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_get_amount_in_exceeds_reserve() {
+        let env = Env::default();
+
+        get_amount_in(&env, 200_0000000, 100_0000000, 200_0000000, 30);
+    }
+
+    /********** quote **********/
+
+    #[test]
+    fn test_quote_rounds_down() {
+        let env = Env::default();
+
+        let result = quote(&env, 1_0000000, 300_0000000, 100_0000000);
+
+        assert_eq!(result, 3333333);
+    }
+
+    /********** get_liquidity_minted **********/
+
+    #[test]
+    fn test_get_liquidity_minted_initial() {
+        let env = Env::default();
+
+        let result = get_liquidity_minted(&env, 100_0000000, 400_0000000, 0, 0, 0);
+
+        assert_eq!(result, 200_0000000);
+    }
+
+    #[test]
+    fn test_get_liquidity_minted_initial_large_amounts_scales() {
+        let env = Env::default();
+
+        let result = get_liquidity_minted(&env, i128::MAX, i128::MAX, 0, 0, 0);
+
+        assert_eq!(result, i128::MAX);
+    }
+
+    #[test]
+    fn test_get_liquidity_minted_uses_min() {
+        let env = Env::default();
+
+        let result = get_liquidity_minted(
+            &env,
+            10_0000000,
+            50_0000000,
+            100_0000000,
+            400_0000000,
+            200_0000000,
+        );
+
+        // token b is over supplied, so token a determines the liquidity
+        assert_eq!(result, 20_0000000);
+    }
+
+    /********** get_amounts_burned **********/
+
+    #[test]
+    fn test_get_amounts_burned_rounds_down() {
+        let env = Env::default();
+
+        let (amount_a, amount_b) =
+            get_amounts_burned(&env, 1_0000000, 100_0000001, 400_0000003, 300_0000000);
+
+        assert_eq!(amount_a, 3333333);
+        assert_eq!(amount_b, 1_3333333);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_get_amounts_burned_exceeds_supply() {
+        let env = Env::default();
+
+        get_amounts_burned(&env, 300_0000001, 100_0000000, 400_0000000, 300_0000000);
+    }
+}
@@ -0,0 +1,7 @@
+/// The number of basis points in 100%
+pub const BPS_SCALAR: i128 = 10_000;
+
+pub mod concentrated_liquidity;
+pub mod constant_product;
+pub mod stable_swap;
+pub mod weighted;
@@ -0,0 +1,284 @@
+use soroban_sdk::{Env, U256};
+
+use crate::SorobanFixedPoint;
+
+/// Errors that can occur while solving the StableSwap invariant
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StableSwapError {
+    /// Newton's method did not converge within the maximum number of iterations
+    NotConverged,
+}
+
+// @dev - the StableSwap invariant for n coins with balances x_i is:
+//
+//   A * n^n * sum(x_i) + D = A * D * n^n + D^(n+1) / (n^n * prod(x_i))
+//
+// Neither D nor a single balance can be solved for in closed form, so both are found with Newton's method.
+// More detail can be found in the StableSwap whitepaper: https://curve.fi/files/stableswap-paper.pdf
+//
+// The solvers only take U256, like Curve's uint256 implementation, so negative balances cannot be passed in.
+// Balances, D and y are never negative, and each subtraction in the iterations stays non-negative, so an I256
+// path would only add sign checks to every step and halve the range. Contracts that track balances as I256
+// should reject negative balances and convert them with `U256::from_be_bytes` before solving.
+//
+/// Parameters for solving the StableSwap (Curve) invariant.
+///
+/// All balances must be normalized to the same number of decimals before being passed in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StableSwap {
+    /// The amplification coefficient A
+    pub amp: u64,
+    /// The maximum number of Newton iterations before returning an error
+    pub max_iterations: u32,
+    /// The maximum difference between two iterations for the result to be considered converged
+    pub tolerance: u64,
+}
+
+impl StableSwap {
+    /// Calculates the invariant D for the pool `balances`, rounding down.
+    ///
+    /// Returns `StableSwapError::NotConverged` if D was not found within `max_iterations`.
+    ///
+    /// ### Panics
+    /// If any balance is 0 while another is not, or an overflow occurs
+    pub fn get_d(&self, env: &Env, balances: &[U256]) -> Result<U256, StableSwapError> {
+        let zero = U256::from_u32(env, 0);
+        let one = U256::from_u32(env, 1);
+        let n = U256::from_u32(env, balances.len() as u32);
+        let sum = balances.iter().fold(zero.clone(), |acc, x| acc.add(x));
+        if sum == zero {
+            return Ok(zero);
+        }
+        let ann = self.ann(env, balances.len() as u32);
+        let tolerance = U256::from_u128(env, self.tolerance as u128);
+
+        let mut d = sum.clone();
+        for _ in 0..self.max_iterations {
+            // d_p = D^(n+1) / (n^n * prod(x_i))
+            let mut d_p = d.clone();
+            for x in balances {
+                d_p = d_p.fixed_mul_floor(env, &d, &x.mul(&n));
+            }
+            let d_prev = d;
+            let numerator = ann.mul(&sum).add(&d_p.mul(&n));
+            let denominator = ann.sub(&one).mul(&d_prev).add(&n.add(&one).mul(&d_p));
+            d = numerator.fixed_mul_floor(env, &d_prev, &denominator);
+            if abs_diff(&d, &d_prev) <= tolerance {
+                return Ok(d);
+            }
+        }
+        Err(StableSwapError::NotConverged)
+    }
+
+    /// Calculates the balance of coin `j` that maintains the invariant `d` after the balance of
+    /// coin `i` is set to `x`, rounding down.
+    ///
+    /// The amount of coin `j` paid out by a swap should be `balances[j] - y - 1`, so that any
+    /// remaining rounding error is in favor of the pool.
+    ///
+    /// Returns `StableSwapError::NotConverged` if y was not found within `max_iterations`.
+    ///
+    /// ### Panics
+    /// If `i` equals `j`, either index is out of bounds, `x` or any other balance is 0, or an
+    /// overflow occurs
+    pub fn get_y(
+        &self,
+        env: &Env,
+        i: usize,
+        j: usize,
+        x: &U256,
+        balances: &[U256],
+        d: &U256,
+    ) -> Result<U256, StableSwapError> {
+        if i == j || i >= balances.len() || j >= balances.len() {
+            panic!("invalid coin index");
+        }
+        let n = U256::from_u32(env, balances.len() as u32);
+        let ann = self.ann(env, balances.len() as u32);
+        let tolerance = U256::from_u128(env, self.tolerance as u128);
+
+        // c = D^(n+1) / (n^n * prod(x_k) * A * n^n) for k != j
+        let mut c = d.clone();
+        let mut sum = U256::from_u32(env, 0);
+        for (k, balance) in balances.iter().enumerate() {
+            let x_k = if k == i {
+                x
+            } else if k != j {
+                balance
+            } else {
+                continue;
+            };
+            sum = sum.add(x_k);
+            c = c.fixed_mul_floor(env, d, &x_k.mul(&n));
+        }
+        c = c.fixed_mul_floor(env, d, &ann.mul(&n));
+        let b = sum.add(&d.div(&ann));
+
+        let mut y = d.clone();
+        for _ in 0..self.max_iterations {
+            let y_prev = y;
+            // y = (y^2 + c) / (2y + b - D)
+            y = y_prev
+                .mul(&y_prev)
+                .add(&c)
+                .div(&y_prev.shl(1).add(&b).sub(d));
+            if abs_diff(&y, &y_prev) <= tolerance {
+                return Ok(y);
+            }
+        }
+        Err(StableSwapError::NotConverged)
+    }
+
+    /// Returns A * n^n
+    fn ann(&self, env: &Env, n: u32) -> U256 {
+        U256::from_u128(env, self.amp as u128).mul(&U256::from_u32(env, n).pow(n))
+    }
+}
+
+/// Performs |x - y|
+fn abs_diff(x: &U256, y: &U256) -> U256 {
+    if x > y {
+        x.sub(y)
+    } else {
+        y.sub(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stable_swap() -> StableSwap {
+        StableSwap {
+            amp: 100,
+            max_iterations: 255,
+            tolerance: 1,
+        }
+    }
+
+    fn balances(env: &Env, balances: &[u128]) -> [U256; 2] {
+        [
+            U256::from_u128(env, balances[0]),
+            U256::from_u128(env, balances[1]),
+        ]
+    }
+
+    /********** get_d **********/
+
+    #[test]
+    fn test_get_d_balanced_pool() {
+        let env = Env::default();
+        let balances = balances(&env, &[1000_0000000, 1000_0000000]);
+
+        let result = stable_swap().get_d(&env, &balances).unwrap();
+
+        assert_eq!(result, U256::from_u128(&env, 2000_0000000));
+    }
+
+    #[test]
+    fn test_get_d_imbalanced_pool() {
+        let env = Env::default();
+        let balances = balances(&env, &[1500_0000000, 500_0000000]);
+
+        let result = stable_swap().get_d(&env, &balances).unwrap();
+
+        // D is slightly less than the sum of the balances
+        assert_eq!(result, U256::from_u128(&env, 1998_3457267));
+    }
+
+    #[test]
+    fn test_get_d_three_coins() {
+        let env = Env::default();
+        let balances = [
+            U256::from_u128(&env, 10u128.pow(30)),
+            U256::from_u128(&env, 10u128.pow(30)),
+            U256::from_u128(&env, 10u128.pow(30)),
+        ];
+
+        let result = stable_swap().get_d(&env, &balances).unwrap();
+
+        assert_eq!(result, U256::from_u128(&env, 3 * 10u128.pow(30)));
+    }
+
+    #[test]
+    fn test_get_d_empty_pool() {
+        let env = Env::default();
+        let balances = balances(&env, &[0, 0]);
+
+        let result = stable_swap().get_d(&env, &balances).unwrap();
+
+        assert_eq!(result, U256::from_u32(&env, 0));
+    }
+
+    #[test]
+    fn test_get_d_not_converged() {
+        let env = Env::default();
+        let balances = balances(&env, &[1500_0000000, 500_0000000]);
+        let stable_swap = StableSwap {
+            max_iterations: 1,
+            ..stable_swap()
+        };
+
+        let result = stable_swap.get_d(&env, &balances);
+
+        assert_eq!(result, Err(StableSwapError::NotConverged));
+    }
+
+    /********** get_y **********/
+
+    #[test]
+    fn test_get_y_swap() {
+        let env = Env::default();
+        let balances = balances(&env, &[1000_0000000, 1000_0000000]);
+        let stable_swap = stable_swap();
+        let d = stable_swap.get_d(&env, &balances).unwrap();
+
+        let x = U256::from_u128(&env, 1010_0000000);
+        let result = stable_swap.get_y(&env, 0, 1, &x, &balances, &d).unwrap();
+
+        // swapping 10 of coin 0 returns just under 10 of coin 1
+        assert_eq!(result, U256::from_u128(&env, 990_0004975));
+    }
+
+    #[test]
+    fn test_get_y_maintains_invariant() {
+        let env = Env::default();
+        let balances = balances(&env, &[1500_0000000, 500_0000000]);
+        let stable_swap = stable_swap();
+        let d = stable_swap.get_d(&env, &balances).unwrap();
+
+        let x = U256::from_u128(&env, 1400_0000000);
+        let y = stable_swap.get_y(&env, 0, 1, &x, &balances, &d).unwrap();
+        let new_d = stable_swap.get_d(&env, &[x, y]).unwrap();
+
+        let one = U256::from_u32(&env, 1);
+        assert!(abs_diff(&new_d, &d) <= one);
+    }
+
+    #[test]
+    fn test_get_y_not_converged() {
+        let env = Env::default();
+        let balances = balances(&env, &[1000_0000000, 1000_0000000]);
+        let d = stable_swap().get_d(&env, &balances).unwrap();
+        let stable_swap = StableSwap {
+            max_iterations: 1,
+            ..stable_swap()
+        };
+
+        let x = U256::from_u128(&env, 1500_0000000);
+        let result = stable_swap.get_y(&env, 0, 1, &x, &balances, &d);
+
+        assert_eq!(result, Err(StableSwapError::NotConverged));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid coin index")]
+    fn test_get_y_same_coin() {
+        let env = Env::default();
+        let balances = balances(&env, &[1000_0000000, 1000_0000000]);
+        let d = U256::from_u128(&env, 2000_0000000);
+
+        let x = U256::from_u128(&env, 1010_0000000);
+        let _ = stable_swap().get_y(&env, 0, 0, &x, &balances, &d);
+    }
+}
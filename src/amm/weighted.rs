@@ -0,0 +1,271 @@
+use soroban_sdk::{unwrap::UnwrapOptimized, Env};
+
+use crate::{log_exp, scale::SCALE_18, SorobanFixedPoint};
+
+/// The maximum amount in for a swap, as a fraction of the input balance (30%)
+pub const MAX_IN_RATIO: i128 = 300_000_000_000_000_000;
+
+/// The maximum amount out for a swap, as a fraction of the output balance (30%)
+pub const MAX_OUT_RATIO: i128 = 300_000_000_000_000_000;
+
+// @dev - math for a weighted (Balancer) pool, with the invariant V = prod(b_i ^ w_i). Every function rounds
+// in favor of the pool. More detail can be found here: https://docs.balancer.fi/concepts/explore-available-balancer-pools/weighted-pool/weighted-math.html
+//
+// All balances, weights and amounts are fixed point numbers with 18 decimals. Token amounts with fewer decimals
+// must be scaled up before use. Weights are only used as ratios, so they do not need to sum to 1.
+//
+// Swap amounts are limited to `MAX_IN_RATIO` and `MAX_OUT_RATIO` of the balances, to keep the error of `pow`
+// bounded. Swap fees are not included, and can be applied to the amount in with `gross_up` or `net_down`.
+//
+// Calculations use `SorobanFixedPoint` so large balances do not cause a phantom overflow. All functions panic
+// if an arithmetic error occurs.
+
+/// Calculates the invariant of the pool, prod(balance_i ^ weight_i), rounding down.
+///
+/// ### Panics
+/// If `balances` and `weights` have different lengths, any balance is negative, or an overflow occurs
+pub fn invariant(env: &Env, balances: &[i128], weights: &[i128]) -> i128 {
+    if balances.len() != weights.len() {
+        panic!("balances and weights have different lengths");
+    }
+    let mut invariant = SCALE_18;
+    for (balance, weight) in balances.iter().zip(weights) {
+        let power = log_exp::pow_down(*balance, *weight).unwrap_optimized();
+        invariant = invariant.fixed_mul_floor(env, &power, &SCALE_18);
+    }
+    invariant
+}
+
+/// Calculates the spot price of the output token in terms of the input token, excluding fees,
+/// or (balance_in / weight_in) / (balance_out / weight_out), rounding up.
+///
+/// ### Panics
+/// If any balance or weight is 0, or an overflow occurs
+pub fn spot_price(
+    env: &Env,
+    balance_in: i128,
+    weight_in: i128,
+    balance_out: i128,
+    weight_out: i128,
+) -> i128 {
+    let numerator = balance_in.fixed_div_ceil(env, &weight_in, &SCALE_18);
+    let denominator = balance_out.fixed_div_floor(env, &weight_out, &SCALE_18);
+    numerator.fixed_div_ceil(env, &denominator, &SCALE_18)
+}
+
+/// Calculates the amount of the output token received for depositing `amount_in` of the input
+/// token, rounding down.
+///
+/// ### Panics
+/// If `amount_in` exceeds `MAX_IN_RATIO` of `balance_in`, any balance or weight is 0, or an
+/// overflow occurs
+pub fn out_given_in(
+    env: &Env,
+    balance_in: i128,
+    weight_in: i128,
+    balance_out: i128,
+    weight_out: i128,
+    amount_in: i128,
+) -> i128 {
+    if amount_in < 0 || amount_in > balance_in.fixed_mul_floor(env, &MAX_IN_RATIO, &SCALE_18) {
+        panic!("invalid amount in");
+    }
+    // amount_out = balance_out * (1 - (balance_in / (balance_in + amount_in))^(weight_in / weight_out))
+    let base = balance_in.fixed_div_ceil(env, &(balance_in + amount_in), &SCALE_18);
+    let exponent = weight_in.fixed_div_floor(env, &weight_out, &SCALE_18);
+    let power = log_exp::pow_up(base, exponent).unwrap_optimized();
+    let complement = (SCALE_18 - power).max(0);
+    balance_out.fixed_mul_floor(env, &complement, &SCALE_18)
+}
+
+/// Calculates the amount of the input token required to receive `amount_out` of the output
+/// token, rounding up.
+///
+/// ### Panics
+/// If `amount_out` exceeds `MAX_OUT_RATIO` of `balance_out`, any balance or weight is 0, or an
+/// overflow occurs
+pub fn in_given_out(
+    env: &Env,
+    balance_in: i128,
+    weight_in: i128,
+    balance_out: i128,
+    weight_out: i128,
+    amount_out: i128,
+) -> i128 {
+    if amount_out < 0 || amount_out > balance_out.fixed_mul_floor(env, &MAX_OUT_RATIO, &SCALE_18) {
+        panic!("invalid amount out");
+    }
+    // amount_in = balance_in * ((balance_out / (balance_out - amount_out))^(weight_out / weight_in) - 1)
+    let base = balance_out.fixed_div_ceil(env, &(balance_out - amount_out), &SCALE_18);
+    let exponent = weight_out.fixed_div_ceil(env, &weight_in, &SCALE_18);
+    let power = log_exp::pow_up(base, exponent).unwrap_optimized();
+    balance_in.fixed_mul_ceil(env, &(power - SCALE_18), &SCALE_18)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WEIGHT_80: i128 = 800_000_000_000_000_000;
+    const WEIGHT_20: i128 = 200_000_000_000_000_000;
+
+    /********** invariant **********/
+
+    #[test]
+    fn test_invariant_equal_weights() {
+        let env = Env::default();
+        let balances = [100 * SCALE_18, 400 * SCALE_18];
+        let weights = [SCALE_18 / 2, SCALE_18 / 2];
+
+        let result = invariant(&env, &balances, &weights);
+
+        // sqrt(100 * 400) = 200
+        assert!(result <= 200 * SCALE_18);
+        assert!(result > 200 * SCALE_18 - 200 * SCALE_18 / 10i128.pow(13));
+    }
+
+    #[test]
+    #[should_panic(expected = "balances and weights have different lengths")]
+    fn test_invariant_length_mismatch() {
+        let env = Env::default();
+
+        invariant(&env, &[SCALE_18], &[SCALE_18, SCALE_18]);
+    }
+
+    /********** spot_price **********/
+
+    #[test]
+    fn test_spot_price() {
+        let env = Env::default();
+
+        let result = spot_price(&env, 200 * SCALE_18, WEIGHT_80, 50 * SCALE_18, WEIGHT_20);
+
+        // (200 / 0.8) / (50 / 0.2) = 1
+        assert_eq!(result, SCALE_18);
+    }
+
+    #[test]
+    fn test_spot_price_rounds_up() {
+        let env = Env::default();
+
+        let result = spot_price(&env, 100 * SCALE_18, SCALE_18, 300 * SCALE_18, SCALE_18);
+
+        assert_eq!(result, 333_333_333_333_333_334);
+    }
+
+    /********** out_given_in **********/
+
+    #[test]
+    fn test_out_given_in_equal_weights_matches_constant_product() {
+        let env = Env::default();
+
+        let result = out_given_in(
+            &env,
+            100 * SCALE_18,
+            SCALE_18,
+            200 * SCALE_18,
+            SCALE_18,
+            10 * SCALE_18,
+        );
+
+        // 200 - 100 * 200 / 110 = 18.181818...
+        let exact: i128 = 18_181_818_181_818_181_818;
+        assert!(result <= exact);
+        assert!(result > exact - exact / 10i128.pow(13));
+    }
+
+    #[test]
+    fn test_out_given_in_weighted() {
+        let env = Env::default();
+
+        let result = out_given_in(
+            &env,
+            100 * SCALE_18,
+            WEIGHT_80,
+            200 * SCALE_18,
+            WEIGHT_20,
+            10 * SCALE_18,
+        );
+
+        // 200 * (1 - (100 / 110)^4) = 63.397308926985861621...
+        let exact: i128 = 63_397_308_926_985_861_621;
+        assert!(result <= exact);
+        assert!(result > exact - exact / 10i128.pow(13));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid amount in")]
+    fn test_out_given_in_exceeds_max_in_ratio() {
+        let env = Env::default();
+
+        out_given_in(
+            &env,
+            100 * SCALE_18,
+            SCALE_18,
+            200 * SCALE_18,
+            SCALE_18,
+            30 * SCALE_18 + 1,
+        );
+    }
+
+    /********** in_given_out **********/
+
+    #[test]
+    fn test_in_given_out_weighted() {
+        let env = Env::default();
+
+        let result = in_given_out(
+            &env,
+            100 * SCALE_18,
+            WEIGHT_80,
+            200 * SCALE_18,
+            WEIGHT_20,
+            10 * SCALE_18,
+        );
+
+        // 100 * ((200 / 190)^0.25 - 1) = 1.290589497996016720...
+        let exact: i128 = 1_290_589_497_996_016_720;
+        assert!(result >= exact);
+        assert!(result < exact + exact / 10i128.pow(12));
+    }
+
+    #[test]
+    fn test_in_given_out_round_trip() {
+        let env = Env::default();
+        let amount_out = out_given_in(
+            &env,
+            100 * SCALE_18,
+            WEIGHT_20,
+            200 * SCALE_18,
+            WEIGHT_80,
+            10 * SCALE_18,
+        );
+
+        let result = in_given_out(
+            &env,
+            100 * SCALE_18,
+            WEIGHT_20,
+            200 * SCALE_18,
+            WEIGHT_80,
+            amount_out,
+        );
+
+        // both directions round in favor of the pool, so the result is only within the error of `pow`
+        assert!((result - 10 * SCALE_18).abs() < 10 * SCALE_18 / 10i128.pow(12));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid amount out")]
+    fn test_in_given_out_exceeds_max_out_ratio() {
+        let env = Env::default();
+
+        in_given_out(
+            &env,
+            100 * SCALE_18,
+            SCALE_18,
+            200 * SCALE_18,
+            SCALE_18,
+            60 * SCALE_18 + 1,
+        );
+    }
+}
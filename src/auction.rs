@@ -0,0 +1,205 @@
+use crate::{
+    log_exp::{self, LN_2, MAX_POW_RELATIVE_ERROR},
+    scale::SCALE_18,
+    FixedPoint,
+};
+
+// @dev - price helpers for Dutch auctions, where a lot or bid is scaled over time. Times can be in any unit,
+// such as seconds or ledgers, as long as all arguments use the same unit.
+//
+// Precision:
+// * `linear_decay` is rounded down
+// * `exponential_decay` halves the value once per whole half life with a shift, and scales the
+//   value by 2^(-r / half_life) for the remaining time r with `exp`. `exp` is not exact, so the factor
+//   is lowered by a relative error of `MAX_POW_RELATIVE_ERROR` plus 1 unit first. The result is never
+//   more than the exact value, and is less by a relative error of at most ~2e-14, plus 1 unit.
+
+/// Calculates the value that moves linearly from `start` to `end` over `duration`, beginning at
+/// `start_time`, at time `now`.
+///
+/// The value is `start` at or before `start_time`, and is clamped to `end` at or after
+/// `start_time + duration`. Rounds down.
+///
+/// Returns None if an overflow occurs.
+pub fn linear_decay(
+    start: i128,
+    end: i128,
+    start_time: u64,
+    duration: u64,
+    now: u64,
+) -> Option<i128> {
+    if now <= start_time {
+        return Some(start);
+    }
+    let elapsed = now - start_time;
+    if elapsed >= duration {
+        return Some(end);
+    }
+    let change = end.checked_sub(start)?;
+    start.checked_add(change.fixed_mul_floor(elapsed as i128, duration as i128)?)
+}
+
+/// Calculates the value that halves every `half_life` from `start`, after `elapsed` time, or
+/// start * 2^(-elapsed / half_life). Rounds down, such that the result is never more than the exact
+/// value.
+///
+/// Returns None if `start` is negative or `half_life` is 0.
+pub fn exponential_decay(start: i128, half_life: u64, elapsed: u64) -> Option<i128> {
+    if start < 0 || half_life == 0 {
+        return None;
+    }
+    let halvings = elapsed / half_life;
+    if halvings >= 127 {
+        // start is at most 2^127 - 1
+        return Some(0);
+    }
+    let value = start >> halvings;
+    let remainder = elapsed % half_life;
+    if remainder == 0 {
+        return Some(value);
+    }
+    // 2^(-r / half_life) = e^(-ln(2) * r / half_life)
+    let exponent = LN_2.fixed_mul_ceil(remainder as i128, half_life as i128)?;
+    let factor = log_exp::exp(-exponent)?;
+    // lower the factor by the maximum error of `exp`, which can overestimate
+    let max_error = factor.fixed_mul_ceil(MAX_POW_RELATIVE_ERROR, SCALE_18)? + 1;
+    let factor = (factor - max_error).max(0);
+    // split value into q * 10^18 + r, so value * factor does not overflow for a factor of at most 1
+    let (q, r) = (value / SCALE_18, value % SCALE_18);
+    q.checked_mul(factor)?
+        .checked_add(r.fixed_mul_floor(factor, SCALE_18)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /********** linear_decay **********/
+
+    #[test]
+    fn test_linear_decay() {
+        let result = linear_decay(100_0000000, 50_0000000, 1000, 100, 1025);
+
+        assert_eq!(result, Some(87_5000000));
+    }
+
+    #[test]
+    fn test_linear_decay_rounds_down() {
+        assert_eq!(linear_decay(10, 0, 0, 3, 1), Some(6));
+        assert_eq!(linear_decay(0, 10, 0, 3, 1), Some(3));
+    }
+
+    #[test]
+    fn test_linear_decay_clamps() {
+        assert_eq!(linear_decay(100, 50, 1000, 100, 0), Some(100));
+        assert_eq!(linear_decay(100, 50, 1000, 100, 1000), Some(100));
+        assert_eq!(linear_decay(100, 50, 1000, 100, 1100), Some(50));
+        assert_eq!(linear_decay(100, 50, 1000, 100, u64::MAX), Some(50));
+        assert_eq!(linear_decay(100, 50, 1000, 0, 1001), Some(50));
+    }
+
+    #[test]
+    fn test_linear_decay_increasing() {
+        let result = linear_decay(0, 1_000_000_000_000_000_000, 0, 17_280, 4_320);
+
+        assert_eq!(result, Some(250_000_000_000_000_000));
+    }
+
+    #[test]
+    fn test_linear_decay_overflow() {
+        assert_eq!(linear_decay(i128::MIN, i128::MAX, 0, 100, 50), None);
+    }
+
+    /********** exponential_decay **********/
+
+    #[test]
+    fn test_exponential_decay() {
+        assert_eq!(exponential_decay(100_0000000, 100, 0), Some(100_0000000));
+        assert_eq!(exponential_decay(100_0000000, 100, 100), Some(50_0000000));
+        assert_eq!(exponential_decay(100_0000000, 100, 300), Some(12_5000000));
+    }
+
+    #[test]
+    fn test_exponential_decay_partial_half_life() {
+        // 100 * 2^(-2.5) = 17.677669529663688...
+        let result = exponential_decay(100_0000000, 100, 250);
+
+        assert_eq!(result, Some(17_6776695));
+    }
+
+    #[test]
+    fn test_exponential_decay_large_value() {
+        let start: i128 = 1_000_000_000_000_000_000_000_000_000;
+
+        // 10^27 * 2^(-1/3) = 793700525984099737375852819.6...
+        let result = exponential_decay(start, 3, 1).unwrap();
+
+        let exact: i128 = 793_700_525_984_099_737_375_852_819;
+        assert!(result <= exact);
+        assert!(result >= exact - exact / 50_000_000_000_000 - 1);
+    }
+
+    #[test]
+    fn test_exponential_decay_max_value() {
+        // i128::MAX * 2^(-1/2) = 120307984584002255772516886238812528462.8...
+        let exact: i128 = 120_307_984_584_002_255_772_516_886_238_812_528_462;
+
+        let result = exponential_decay(i128::MAX, 2, 1).unwrap();
+
+        assert!(result <= exact);
+        assert!(result >= exact - exact / 50_000_000_000_000 - 1);
+    }
+
+    #[test]
+    fn test_exponential_decay_never_overshoots() {
+        let start: i128 = 1_000_000_000_000_000_000_000_000_000;
+
+        // floor(10^27 * 2^(-1/100))
+        let exact: i128 = 993_092_495_437_035_901_533_210_216;
+        let result = exponential_decay(start, 100, 1).unwrap();
+        assert!(result <= exact);
+        assert!(result >= exact - exact / 50_000_000_000_000 - 1);
+
+        // floor(10^27 * 2^(-r/16)) for r in 1..16
+        let exact: [i128; 15] = [
+            957_603_280_698_573_646_936_305_635,
+            917_004_043_204_671_231_743_541_594,
+            878_126_080_186_649_741_556_080_309,
+            840_896_415_253_714_543_031_125_476,
+            805_245_165_974_627_154_089_760_333,
+            771_105_412_703_970_411_806_145_931,
+            738_413_072_969_749_655_693_453_740,
+            707_106_781_186_547_524_400_844_362,
+            677_127_773_468_446_364_149_007_370,
+            648_419_777_325_504_832_966_877_058,
+            620_928_906_036_742_024_296_838_734,
+            594_603_557_501_360_533_358_749_985,
+            569_394_317_378_345_826_851_915_141,
+            545_253_866_332_628_829_603_505_327,
+            522_136_891_213_706_920_160_983_239,
+        ];
+        for (remainder, exact) in (1..16).zip(exact) {
+            let result = exponential_decay(start, 16, remainder).unwrap();
+            assert!(result <= exact);
+            assert!(result >= exact - exact / 50_000_000_000_000 - 1);
+
+            // a whole half life only shifts the value
+            let result = exponential_decay(start, 16, 16 + remainder).unwrap();
+            assert!(result <= exact / 2);
+            assert!(result >= exact / 2 - exact / 100_000_000_000_000 - 1);
+        }
+    }
+
+    #[test]
+    fn test_exponential_decay_clamps_to_zero() {
+        assert_eq!(exponential_decay(i128::MAX, 1, 126), Some(1));
+        assert_eq!(exponential_decay(i128::MAX, 1, 127), Some(0));
+        assert_eq!(exponential_decay(i128::MAX, 1, u64::MAX), Some(0));
+    }
+
+    #[test]
+    fn test_exponential_decay_invalid() {
+        assert_eq!(exponential_decay(-1, 100, 10), None);
+        assert_eq!(exponential_decay(100, 0, 10), None);
+    }
+}
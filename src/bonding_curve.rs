@@ -0,0 +1,457 @@
+use soroban_sdk::{unwrap::UnwrapOptimized, Env};
+
+use crate::{
+    log_exp::{self, MAX_POW_RELATIVE_ERROR},
+    scale::SCALE_18,
+    Rounding, SorobanFixedPoint,
+};
+
+// @dev - all supplies, amounts, prices, and curve parameters are fixed point numbers with 18 decimals.
+//
+// The cost to buy `amount` tokens at `supply` is the area under the price curve p(s) from `supply` to
+// `supply + amount`, and the return for selling is the area from `supply - amount` to `supply`. Each curve
+// integrates p(s) in closed form:
+//
+// * linear:      p(s) = base + slope * s               => base * s + slope * s^2 / 2
+// * polynomial:  p(s) = c * s^n                        => c * s^(n+1) / (n+1)
+// * exponential: p(s) = a * e^(b*s)                    => a / b * e^(b*s)
+// * sigmoid:     p(s) = max / (1 + e^(-k * (s - m)))   => max / k * ln(1 + e^(k * (s - m)))
+//
+// Precision:
+// * every step rounds in favor of the curve, so `buy_cost` is never less than, and `sell_return` is never
+//   more than, the exact area. Buying and then selling the same amount never returns more than it cost.
+// * `exp` and `ln` are not exact, so the exponential curve widens each bound by a relative error of
+//   `MAX_POW_RELATIVE_ERROR` plus 1 unit, and the sigmoid curve by `MAX_SOFTPLUS_ERROR`. This adds a
+//   small fixed cost per trade in favor of the curve.
+
+/// The maximum absolute error of `softplus`, 1e-14, as a fixed point number with 18 decimals.
+const MAX_SOFTPLUS_ERROR: i128 = 10_000;
+
+/// A bonding curve that prices a token by its supply.
+///
+/// The area under the curve is computed with the `SorobanFixedPoint` methods, so all arithmetic
+/// errors panic.
+pub trait BondingCurve {
+    /// Calculates the area under the price curve from `from` to `to` supply, rounded according
+    /// to `rounding`.
+    ///
+    /// ### Panics
+    /// If `from` is negative, `to` is less than `from`, the curve parameters are invalid, or an
+    /// overflow occurs.
+    fn integral(&self, env: &Env, from: i128, to: i128, rounding: Rounding) -> i128;
+
+    /// Calculates the cost to buy `amount` tokens when the current supply is `supply`. Rounds up.
+    ///
+    /// ### Panics
+    /// If `supply` or `amount` is negative, the curve parameters are invalid, or an overflow
+    /// occurs.
+    fn buy_cost(&self, env: &Env, supply: i128, amount: i128) -> i128 {
+        if amount < 0 {
+            panic!("invalid amount");
+        }
+        let to = supply.checked_add(amount).unwrap_optimized();
+        self.integral(env, supply, to, Rounding::Ceil)
+    }
+
+    /// Calculates the return for selling `amount` tokens when the current supply is `supply`.
+    /// Rounds down.
+    ///
+    /// ### Panics
+    /// If `amount` is negative or greater than `supply`, the curve parameters are invalid, or an
+    /// overflow occurs.
+    fn sell_return(&self, env: &Env, supply: i128, amount: i128) -> i128 {
+        if amount < 0 || amount > supply {
+            panic!("invalid amount");
+        }
+        self.integral(env, supply - amount, supply, Rounding::Floor)
+    }
+}
+
+/// A linear bonding curve, p(s) = base_price + slope * s
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LinearCurve {
+    /// The price at a supply of 0
+    pub base_price: i128,
+    /// The increase in price per token of supply
+    pub slope: i128,
+}
+
+impl BondingCurve for LinearCurve {
+    fn integral(&self, env: &Env, from: i128, to: i128, rounding: Rounding) -> i128 {
+        check_range(from, to);
+        if self.base_price < 0 || self.slope < 0 {
+            panic!("invalid curve");
+        }
+        // base * (to - from) + slope * (to - from) * (to + from) / 2
+        let width = to - from;
+        let base_area = mul(env, self.base_price, width, SCALE_18, rounding);
+        let sum = to.checked_add(from).unwrap_optimized();
+        let width_sum = mul(env, width, sum, SCALE_18, rounding);
+        let slope_area = mul(env, self.slope, width_sum, 2 * SCALE_18, rounding);
+        base_area.checked_add(slope_area).unwrap_optimized()
+    }
+}
+
+/// A polynomial bonding curve, p(s) = coefficient * s^exponent
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PolynomialCurve {
+    /// The price at a supply of 1 token
+    pub coefficient: i128,
+    /// The integer exponent of the supply
+    pub exponent: u32,
+}
+
+impl BondingCurve for PolynomialCurve {
+    fn integral(&self, env: &Env, from: i128, to: i128, rounding: Rounding) -> i128 {
+        check_range(from, to);
+        if self.coefficient < 0 {
+            panic!("invalid curve");
+        }
+        // c * (to^(n+1) - from^(n+1)) / (n+1)
+        let degree = self.exponent.checked_add(1).unwrap_optimized();
+        let upper = pow(env, to, degree, rounding);
+        let lower = pow(env, from, degree, reverse(rounding));
+        let difference = (upper - lower).max(0);
+        let denominator = SCALE_18.checked_mul(degree as i128).unwrap_optimized();
+        mul(env, self.coefficient, difference, denominator, rounding)
+    }
+}
+
+/// An exponential bonding curve, p(s) = initial_price * e^(growth_rate * s)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExponentialCurve {
+    /// The price at a supply of 0
+    pub initial_price: i128,
+    /// The continuous growth rate of the price per token of supply. Must be positive.
+    pub growth_rate: i128,
+}
+
+impl BondingCurve for ExponentialCurve {
+    fn integral(&self, env: &Env, from: i128, to: i128, rounding: Rounding) -> i128 {
+        check_range(from, to);
+        if self.initial_price < 0 || self.growth_rate <= 0 {
+            panic!("invalid curve");
+        }
+        // a / b * (e^(b * to) - e^(b * from))
+        let upper_exponent = mul(env, self.growth_rate, to, SCALE_18, rounding);
+        let lower_exponent = mul(env, self.growth_rate, from, SCALE_18, reverse(rounding));
+        let upper = exp(upper_exponent, rounding);
+        let lower = exp(lower_exponent, reverse(rounding));
+        let difference = (upper - lower).max(0);
+        mul(
+            env,
+            self.initial_price,
+            difference,
+            self.growth_rate,
+            rounding,
+        )
+    }
+}
+
+/// A sigmoid bonding curve, p(s) = max_price / (1 + e^(-steepness * (s - midpoint)))
+///
+/// The price starts near 0, reaches half of `max_price` at `midpoint`, and approaches
+/// `max_price` as the supply grows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SigmoidCurve {
+    /// The price the curve approaches as the supply grows
+    pub max_price: i128,
+    /// The steepness of the curve per token of supply. Must be positive.
+    pub steepness: i128,
+    /// The supply where the price is half of `max_price`
+    pub midpoint: i128,
+}
+
+impl BondingCurve for SigmoidCurve {
+    fn integral(&self, env: &Env, from: i128, to: i128, rounding: Rounding) -> i128 {
+        check_range(from, to);
+        if self.max_price < 0 || self.steepness <= 0 {
+            panic!("invalid curve");
+        }
+        // max / k * (ln(1 + e^(k * (to - m))) - ln(1 + e^(k * (from - m))))
+        let upper_offset = to.checked_sub(self.midpoint).unwrap_optimized();
+        let lower_offset = from.checked_sub(self.midpoint).unwrap_optimized();
+        let upper_exponent = mul(env, self.steepness, upper_offset, SCALE_18, rounding);
+        let lower_exponent = mul(
+            env,
+            self.steepness,
+            lower_offset,
+            SCALE_18,
+            reverse(rounding),
+        );
+        let upper = softplus(upper_exponent, rounding);
+        let lower = softplus(lower_exponent, reverse(rounding));
+        let difference = (upper - lower).max(0);
+        mul(env, self.max_price, difference, self.steepness, rounding)
+    }
+}
+
+/// Verifies 0 <= from <= to
+fn check_range(from: i128, to: i128) {
+    if from < 0 || to < from {
+        panic!("invalid supply range");
+    }
+}
+
+/// Returns the opposite rounding direction
+fn reverse(rounding: Rounding) -> Rounding {
+    match rounding {
+        Rounding::Floor => Rounding::Ceil,
+        Rounding::Ceil => Rounding::Floor,
+    }
+}
+
+/// Performs x * y / z, rounded according to `rounding`
+fn mul(env: &Env, x: i128, y: i128, z: i128, rounding: Rounding) -> i128 {
+    match rounding {
+        Rounding::Floor => x.fixed_mul_floor(env, &y, &z),
+        Rounding::Ceil => x.fixed_mul_ceil(env, &y, &z),
+    }
+}
+
+/// Performs x^n for a non-negative x, rounding each multiplication according to `rounding`
+fn pow(env: &Env, x: i128, n: u32, rounding: Rounding) -> i128 {
+    let mut result = SCALE_18;
+    for _ in 0..n {
+        result = mul(env, result, x, SCALE_18, rounding);
+    }
+    result
+}
+
+/// Performs e^x, widened by the maximum error of `exp` according to `rounding`
+///
+/// ### Panics
+/// If x is greater than `MAX_NATURAL_EXPONENT`
+fn exp(x: i128, rounding: Rounding) -> i128 {
+    let result = log_exp::exp(x).unwrap_optimized();
+    let max_error = crate::FixedPoint::fixed_mul_ceil(result, MAX_POW_RELATIVE_ERROR, SCALE_18)
+        .unwrap_optimized()
+        + 1;
+    match rounding {
+        Rounding::Floor => (result - max_error).max(0),
+        Rounding::Ceil => result.checked_add(max_error).unwrap_optimized(),
+    }
+}
+
+/// Performs ln(1 + e^x), widened by `MAX_SOFTPLUS_ERROR` according to `rounding`
+fn softplus(x: i128, rounding: Rounding) -> i128 {
+    // ln(1 + e^x) = x + ln(1 + e^-x), so e^(-|x|) is at most 1 and never overflows
+    let tail = log_exp::exp(-x.abs()).unwrap_optimized();
+    let result = log_exp::ln(SCALE_18 + tail).unwrap_optimized() + x.max(0);
+    match rounding {
+        Rounding::Floor => (result - MAX_SOFTPLUS_ERROR).max(0),
+        Rounding::Ceil => result.checked_add(MAX_SOFTPLUS_ERROR).unwrap_optimized(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Asserts |result - expected| <= tolerance
+    fn assert_approx_eq(result: i128, expected: i128, tolerance: i128) {
+        assert!(
+            (result - expected).abs() <= tolerance,
+            "result: {}, expected: {}",
+            result,
+            expected
+        );
+    }
+
+    /// Asserts buying `amount` at `supply` and selling it back never returns more than it cost
+    fn assert_round_trip(curve: &impl BondingCurve, env: &Env, supply: i128, amount: i128) {
+        let cost = curve.buy_cost(env, supply, amount);
+        let proceeds = curve.sell_return(env, supply + amount, amount);
+        assert!(proceeds <= cost, "proceeds: {}, cost: {}", proceeds, cost);
+    }
+
+    /********** LinearCurve **********/
+
+    #[test]
+    fn test_linear_buy_cost() {
+        let env = Env::default();
+        let curve = LinearCurve {
+            base_price: SCALE_18,
+            slope: SCALE_18 / 100,
+        };
+
+        // 1 * 10 + 0.01 * (20^2 - 10^2) / 2 = 11.5
+        let result = curve.buy_cost(&env, 10 * SCALE_18, 10 * SCALE_18);
+
+        assert_eq!(result, 11_500_000_000_000_000_000);
+    }
+
+    #[test]
+    fn test_linear_rounding() {
+        let env = Env::default();
+        let curve = LinearCurve {
+            base_price: 1,
+            slope: 3,
+        };
+
+        let cost = curve.buy_cost(&env, SCALE_18, 1);
+        let proceeds = curve.sell_return(&env, SCALE_18 + 1, 1);
+
+        // 1e-18 + 3e-18 * (2 + 1e-18) * 1e-18 / 2, where each term is rounded
+        assert_eq!(cost, 2);
+        assert_eq!(proceeds, 0);
+        assert_eq!(curve.buy_cost(&env, 0, 0), 0);
+    }
+
+    #[test]
+    fn test_linear_sell_return() {
+        let env = Env::default();
+        let curve = LinearCurve {
+            base_price: SCALE_18,
+            slope: SCALE_18 / 100,
+        };
+
+        let result = curve.sell_return(&env, 20 * SCALE_18, 10 * SCALE_18);
+
+        assert_eq!(result, 11_500_000_000_000_000_000);
+        assert_round_trip(&curve, &env, 123_456_789_123_456_789, 987_654_321);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid amount")]
+    fn test_linear_sell_more_than_supply() {
+        let env = Env::default();
+        let curve = LinearCurve {
+            base_price: SCALE_18,
+            slope: 0,
+        };
+
+        curve.sell_return(&env, SCALE_18, SCALE_18 + 1);
+    }
+
+    /********** PolynomialCurve **********/
+
+    #[test]
+    fn test_polynomial_buy_cost() {
+        let env = Env::default();
+        let curve = PolynomialCurve {
+            coefficient: 3 * SCALE_18,
+            exponent: 2,
+        };
+
+        // 3 * (3^3 - 2^3) / 3 = 19
+        let cost = curve.buy_cost(&env, 2 * SCALE_18, SCALE_18);
+        let proceeds = curve.sell_return(&env, 3 * SCALE_18, SCALE_18);
+
+        assert_eq!(cost, 19 * SCALE_18);
+        assert_eq!(proceeds, 19 * SCALE_18);
+    }
+
+    #[test]
+    fn test_polynomial_rounding() {
+        let env = Env::default();
+        let curve = PolynomialCurve {
+            coefficient: SCALE_18,
+            exponent: 2,
+        };
+
+        // (1.5^3 - 1.4^3) / 3 = 0.210333...
+        let cost = curve.buy_cost(&env, 1_400_000_000_000_000_000, SCALE_18 / 10);
+        let proceeds = curve.sell_return(&env, 1_500_000_000_000_000_000, SCALE_18 / 10);
+
+        assert_eq!(cost, 210_333_333_333_333_334);
+        assert_eq!(proceeds, 210_333_333_333_333_333);
+        assert_round_trip(&curve, &env, 7, 3);
+    }
+
+    #[test]
+    fn test_polynomial_constant() {
+        let env = Env::default();
+        let curve = PolynomialCurve {
+            coefficient: 2 * SCALE_18,
+            exponent: 0,
+        };
+
+        let result = curve.buy_cost(&env, 1_000 * SCALE_18, 5 * SCALE_18);
+
+        assert_eq!(result, 10 * SCALE_18);
+    }
+
+    /********** ExponentialCurve **********/
+
+    #[test]
+    fn test_exponential_buy_cost() {
+        let env = Env::default();
+        let curve = ExponentialCurve {
+            initial_price: SCALE_18,
+            growth_rate: SCALE_18 / 10,
+        };
+
+        // 1 / 0.1 * (e^1 - e^0) = 17.182818284590452353...
+        let cost = curve.buy_cost(&env, 0, 10 * SCALE_18);
+        let proceeds = curve.sell_return(&env, 10 * SCALE_18, 10 * SCALE_18);
+
+        assert!(cost >= 17_182_818_284_590_452_354);
+        assert!(proceeds <= 17_182_818_284_590_452_353);
+        assert_approx_eq(cost, 17_182_818_284_590_452_354, 10_000_000);
+        assert_approx_eq(proceeds, 17_182_818_284_590_452_353, 10_000_000);
+    }
+
+    #[test]
+    fn test_exponential_round_trip() {
+        let env = Env::default();
+        let curve = ExponentialCurve {
+            initial_price: 1_000_000,
+            growth_rate: 3 * SCALE_18,
+        };
+
+        assert_round_trip(&curve, &env, 5 * SCALE_18, 1);
+        assert_round_trip(&curve, &env, 5 * SCALE_18, SCALE_18);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid curve")]
+    fn test_exponential_invalid_growth_rate() {
+        let env = Env::default();
+        let curve = ExponentialCurve {
+            initial_price: SCALE_18,
+            growth_rate: 0,
+        };
+
+        curve.buy_cost(&env, 0, SCALE_18);
+    }
+
+    /********** SigmoidCurve **********/
+
+    #[test]
+    fn test_sigmoid_symmetric_around_midpoint() {
+        let env = Env::default();
+        let curve = SigmoidCurve {
+            max_price: 10 * SCALE_18,
+            steepness: SCALE_18,
+            midpoint: 100 * SCALE_18,
+        };
+
+        // the price averages to max / 2 over any range centered on the midpoint
+        let cost = curve.buy_cost(&env, 99 * SCALE_18, 2 * SCALE_18);
+        let proceeds = curve.sell_return(&env, 101 * SCALE_18, 2 * SCALE_18);
+
+        assert!(cost >= 10 * SCALE_18);
+        assert!(proceeds <= 10 * SCALE_18);
+        assert_approx_eq(cost, 10 * SCALE_18, 1_000_000);
+        assert_approx_eq(proceeds, 10 * SCALE_18, 1_000_000);
+    }
+
+    #[test]
+    fn test_sigmoid_far_from_midpoint() {
+        let env = Env::default();
+        let curve = SigmoidCurve {
+            max_price: 10 * SCALE_18,
+            steepness: SCALE_18,
+            midpoint: 100 * SCALE_18,
+        };
+
+        // the price is ~max well above the midpoint, and ~0 well below it
+        let high = curve.buy_cost(&env, 200 * SCALE_18, SCALE_18);
+        let low = curve.sell_return(&env, 2 * SCALE_18, SCALE_18);
+
+        assert_approx_eq(high, 10 * SCALE_18, 1_000_000);
+        assert_eq!(low, 0);
+        assert_round_trip(&curve, &env, 150 * SCALE_18, 12_345);
+    }
+}
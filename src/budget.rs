@@ -0,0 +1,97 @@
+//! Helpers for budget regression tests. The `baseline_*` functions are the mul div paths from before
+//! host calls were reduced, so tests can assert the current paths cost less.
+
+use soroban_sdk::{unwrap::UnwrapOptimized, Env, I256, U256};
+
+/// Returns the result of `f`, and the CPU instructions and memory bytes it consumed
+pub(crate) fn measure<R>(env: &Env, f: impl FnOnce() -> R) -> (R, u64, u64) {
+    let mut budget = env.cost_estimate().budget();
+    budget.reset_unlimited();
+    let result = f();
+    (
+        result,
+        budget.cpu_instruction_cost(),
+        budget.memory_bytes_cost(),
+    )
+}
+
+/// Performs floor(x * y / z), with a remainder and a subtraction for every negative result
+pub(crate) fn baseline_i256_mul_div_floor(env: &Env, x: &I256, y: &I256, z: &I256) -> I256 {
+    let zero = I256::from_i32(env, 0);
+    let r = x.mul(y);
+    if r < zero || (r > zero && *z < zero) {
+        let remainder = r.rem_euclid(z);
+        let one = I256::from_i32(env, 1);
+        r.div(z).sub(if remainder > zero { &one } else { &zero })
+    } else {
+        r.div(z)
+    }
+}
+
+/// Performs ceil(x * y / z), with a remainder and an addition for every positive result
+pub(crate) fn baseline_i256_mul_div_ceil(env: &Env, x: &I256, y: &I256, z: &I256) -> I256 {
+    let zero = I256::from_i32(env, 0);
+    let r = x.mul(y);
+    if r <= zero || *z < zero {
+        r.div(z)
+    } else {
+        let remainder = r.rem_euclid(z);
+        let one = I256::from_i32(env, 1);
+        r.div(z).add(if remainder > zero { &one } else { &zero })
+    }
+}
+
+/// Performs ceil(x * y / z), with a remainder and an addition for every result
+pub(crate) fn baseline_u256_mul_div_ceil(env: &Env, x: &U256, y: &U256, z: &U256) -> U256 {
+    let r = x.mul(y);
+    let remainder = r.rem_euclid(z);
+    let zero = U256::from_u32(env, 0);
+    let one = U256::from_u32(env, 1);
+    r.div(z).add(if remainder > zero { &one } else { &zero })
+}
+
+/// Performs floor(x * y / z) in I256 math, converting with the `I256` methods
+pub(crate) fn baseline_i128_mul_div_floor(env: &Env, x: i128, y: i128, z: i128) -> i128 {
+    let (x, y, z) = (
+        I256::from_i128(env, x),
+        I256::from_i128(env, y),
+        I256::from_i128(env, z),
+    );
+    baseline_i256_mul_div_floor(env, &x, &y, &z)
+        .to_i128()
+        .unwrap_optimized()
+}
+
+/// Performs ceil(x * y / z) in I256 math, converting with the `I256` methods
+pub(crate) fn baseline_i128_mul_div_ceil(env: &Env, x: i128, y: i128, z: i128) -> i128 {
+    let (x, y, z) = (
+        I256::from_i128(env, x),
+        I256::from_i128(env, y),
+        I256::from_i128(env, z),
+    );
+    baseline_i256_mul_div_ceil(env, &x, &y, &z)
+        .to_i128()
+        .unwrap_optimized()
+}
+
+/// Performs floor(x * y / z) in U256 math, converting with the `U256` methods
+pub(crate) fn baseline_u128_mul_div_floor(env: &Env, x: u128, y: u128, z: u128) -> u128 {
+    let (x, y, z) = (
+        U256::from_u128(env, x),
+        U256::from_u128(env, y),
+        U256::from_u128(env, z),
+    );
+    x.mul(&y).div(&z).to_u128().unwrap_optimized()
+}
+
+/// Performs ceil(x * y / z) in U256 math, converting with the `U256` methods
+pub(crate) fn baseline_u128_mul_div_ceil(env: &Env, x: u128, y: u128, z: u128) -> u128 {
+    let (x, y, z) = (
+        U256::from_u128(env, x),
+        U256::from_u128(env, y),
+        U256::from_u128(env, z),
+    );
+    baseline_u256_mul_div_ceil(env, &x, &y, &z)
+        .to_u128()
+        .unwrap_optimized()
+}
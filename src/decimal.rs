@@ -0,0 +1,1005 @@
+use soroban_sdk::{unwrap::UnwrapOptimized, Bytes, Env, String, I256, U256};
+
+use crate::Rounding;
+
+/// The maximum number of decimals supported when formatting a decimal string
+pub const MAX_DECIMALS: u32 = 77;
+
+/// The number of digits in the largest U256
+const MAX_DIGITS: usize = 78;
+
+/// The length of the longest formatted value, such as "-0." followed by `MAX_DECIMALS` digits
+const MAX_LEN: usize = MAX_DECIMALS as usize + 3;
+
+/// The number of digits converted from an I256 or U256 per host division
+const CHUNK_DIGITS: usize = 19;
+
+/// 10^CHUNK_DIGITS
+const CHUNK: u128 = 10_000_000_000_000_000_000;
+
+/// How trailing zeros in the fractional part of a decimal string are formatted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrailingZeros {
+    /// Always include `decimals` digits after the decimal point, e.g. "1.5000000"
+    Keep,
+    /// Remove trailing zeros, and the decimal point if nothing follows it, e.g. "1.5"
+    Trim,
+}
+
+/// Decimal string trait for formatting fixed point numbers as human readable strings.
+///
+/// Values are formatted with a leading "-" if negative, at least one integer digit, and a "."
+/// before any fractional digits. For example, -123456789 with 7 decimals is "-12.3456789".
+pub trait ToDecimalString {
+    /// Formats x, a fixed point number with `decimals` decimals, as a `String`.
+    ///
+    /// ### Panics
+    /// This method will panic if `decimals` is greater than `MAX_DECIMALS`.
+    fn to_decimal_string(&self, env: &Env, decimals: u32, trailing_zeros: TrailingZeros) -> String;
+
+    /// Formats x, a fixed point number with `decimals` decimals, as UTF-8 `Bytes`.
+    ///
+    /// ### Panics
+    /// This method will panic if `decimals` is greater than `MAX_DECIMALS`.
+    fn to_decimal_bytes(&self, env: &Env, decimals: u32, trailing_zeros: TrailingZeros) -> Bytes;
+}
+
+impl ToDecimalString for i128 {
+    fn to_decimal_string(&self, env: &Env, decimals: u32, trailing_zeros: TrailingZeros) -> String {
+        let digits = Digits::from_u128(self.unsigned_abs());
+        let decimal = Decimal::new(*self < 0, &digits, decimals, trailing_zeros);
+        String::from_bytes(env, decimal.as_bytes())
+    }
+
+    fn to_decimal_bytes(&self, env: &Env, decimals: u32, trailing_zeros: TrailingZeros) -> Bytes {
+        let digits = Digits::from_u128(self.unsigned_abs());
+        let decimal = Decimal::new(*self < 0, &digits, decimals, trailing_zeros);
+        Bytes::from_slice(env, decimal.as_bytes())
+    }
+}
+
+impl ToDecimalString for u128 {
+    fn to_decimal_string(&self, env: &Env, decimals: u32, trailing_zeros: TrailingZeros) -> String {
+        let digits = Digits::from_u128(*self);
+        let decimal = Decimal::new(false, &digits, decimals, trailing_zeros);
+        String::from_bytes(env, decimal.as_bytes())
+    }
+
+    fn to_decimal_bytes(&self, env: &Env, decimals: u32, trailing_zeros: TrailingZeros) -> Bytes {
+        let digits = Digits::from_u128(*self);
+        let decimal = Decimal::new(false, &digits, decimals, trailing_zeros);
+        Bytes::from_slice(env, decimal.as_bytes())
+    }
+}
+
+impl ToDecimalString for I256 {
+    fn to_decimal_string(&self, env: &Env, decimals: u32, trailing_zeros: TrailingZeros) -> String {
+        let (negative, digits) = i256_digits(env, self);
+        let decimal = Decimal::new(negative, &digits, decimals, trailing_zeros);
+        String::from_bytes(env, decimal.as_bytes())
+    }
+
+    fn to_decimal_bytes(&self, env: &Env, decimals: u32, trailing_zeros: TrailingZeros) -> Bytes {
+        let (negative, digits) = i256_digits(env, self);
+        let decimal = Decimal::new(negative, &digits, decimals, trailing_zeros);
+        Bytes::from_slice(env, decimal.as_bytes())
+    }
+}
+
+impl ToDecimalString for U256 {
+    fn to_decimal_string(&self, env: &Env, decimals: u32, trailing_zeros: TrailingZeros) -> String {
+        let digits = u256_digits(env, self);
+        let decimal = Decimal::new(false, &digits, decimals, trailing_zeros);
+        String::from_bytes(env, decimal.as_bytes())
+    }
+
+    fn to_decimal_bytes(&self, env: &Env, decimals: u32, trailing_zeros: TrailingZeros) -> Bytes {
+        let digits = u256_digits(env, self);
+        let decimal = Decimal::new(false, &digits, decimals, trailing_zeros);
+        Bytes::from_slice(env, decimal.as_bytes())
+    }
+}
+
+/// Returns the sign and the digits of the magnitude of x
+fn i256_digits(env: &Env, x: &I256) -> (bool, Digits) {
+    let zero = I256::from_i32(env, 0);
+    let chunk = crate::i256::from_i128(env, CHUNK as i128);
+    let negative = *x < zero;
+    // division truncates toward zero, so the remainders share the sign of x and I256::MIN
+    // never needs to be negated
+    let mut digits = Digits::new();
+    let mut value = x.clone();
+    loop {
+        let quotient = value.div(&chunk);
+        let remainder = crate::i256::to_i128(&value.sub(&quotient.mul(&chunk))).unwrap_optimized();
+        let is_last = quotient == zero;
+        digits.push_u128(remainder.unsigned_abs(), !is_last);
+        if is_last {
+            return (negative, digits);
+        }
+        value = quotient;
+    }
+}
+
+/// Returns the digits of x
+fn u256_digits(env: &Env, x: &U256) -> Digits {
+    let zero = U256::from_u32(env, 0);
+    let chunk = crate::u256::from_u128(env, CHUNK);
+    let mut digits = Digits::new();
+    let mut value = x.clone();
+    loop {
+        let quotient = value.div(&chunk);
+        let remainder = crate::u256::to_u128(&value.sub(&quotient.mul(&chunk))).unwrap_optimized();
+        let is_last = quotient == zero;
+        digits.push_u128(remainder, !is_last);
+        if is_last {
+            return digits;
+        }
+        value = quotient;
+    }
+}
+
+/// The ASCII decimal digits of an unsigned integer, without leading zeros
+pub(crate) struct Digits {
+    buf: [u8; MAX_DIGITS],
+    start: usize,
+}
+
+impl Digits {
+    fn new() -> Self {
+        Digits {
+            buf: [b'0'; MAX_DIGITS],
+            start: MAX_DIGITS,
+        }
+    }
+
+    /// Returns the digits of x
+    pub(crate) fn from_u128(x: u128) -> Self {
+        let mut digits = Digits::new();
+        digits.push_u128(x, false);
+        digits
+    }
+
+    /// Writes x in front of the existing digits, padded to `CHUNK_DIGITS` digits if `pad` is set
+    fn push_u128(&mut self, mut x: u128, pad: bool) {
+        let end = self.start;
+        loop {
+            self.start -= 1;
+            self.buf[self.start] = b'0' + (x % 10) as u8;
+            x /= 10;
+            if x == 0 {
+                break;
+            }
+        }
+        if pad {
+            // the buffer is initialized with zeros
+            self.start = end - CHUNK_DIGITS;
+        }
+    }
+
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.buf[self.start..]
+    }
+}
+
+/// A fixed point number formatted as an ASCII decimal string
+pub(crate) struct Decimal {
+    buf: [u8; MAX_LEN],
+    len: usize,
+}
+
+impl Decimal {
+    /// Formats the digits of a fixed point number with `decimals` decimals
+    ///
+    /// ### Panics
+    /// If `decimals` is greater than `MAX_DECIMALS`
+    pub(crate) fn new(
+        negative: bool,
+        digits: &Digits,
+        decimals: u32,
+        trailing_zeros: TrailingZeros,
+    ) -> Self {
+        if decimals > MAX_DECIMALS {
+            panic!("decimals too large");
+        }
+        let decimals = decimals as usize;
+        let digits = digits.as_bytes();
+        let mut decimal = Decimal {
+            buf: [b'0'; MAX_LEN],
+            len: 0,
+        };
+
+        // a zero value has a single "0" digit and no sign
+        if negative && digits != b"0" {
+            decimal.push(b'-');
+        }
+        let (integer, fraction) = if digits.len() > decimals {
+            digits.split_at(digits.len() - decimals)
+        } else {
+            (&b"0"[..], digits)
+        };
+        decimal.extend(integer);
+
+        let leading_zeros = decimals - fraction.len();
+        let fraction = match trailing_zeros {
+            TrailingZeros::Keep => fraction,
+            TrailingZeros::Trim => {
+                let end = fraction
+                    .iter()
+                    .rposition(|d| *d != b'0')
+                    .map_or(0, |i| i + 1);
+                &fraction[..end]
+            }
+        };
+        if !fraction.is_empty() || (trailing_zeros == TrailingZeros::Keep && decimals > 0) {
+            decimal.push(b'.');
+            // the buffer is initialized with zeros
+            decimal.len += leading_zeros;
+            decimal.extend(fraction);
+        }
+        decimal
+    }
+
+    fn push(&mut self, byte: u8) {
+        self.buf[self.len] = byte;
+        self.len += 1;
+    }
+
+    fn extend(&mut self, bytes: &[u8]) {
+        self.buf[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+    }
+
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
+/// The maximum length of a `String` that can be parsed
+pub const MAX_PARSE_LEN: u32 = 256;
+
+/// Errors that can occur while parsing a decimal string
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The string is not a valid decimal number, or is longer than `MAX_PARSE_LEN`
+    InvalidFormat,
+    /// The string has more decimals than requested, and no rounding was chosen
+    ExcessPrecision,
+    /// The value does not fit in the target type
+    Overflow,
+}
+
+/// Decimal parsing trait for converting human readable strings into fixed point numbers.
+///
+/// Accepts an optional sign, digits with an optional ".", and an optional exponent, such as
+/// "-12.3456789", "+.5", "1.", or "1.5e-3". Whitespace and digit separators are not accepted.
+pub trait ParseDecimal: Sized {
+    /// Parses `s` into a fixed point number with `decimals` decimals.
+    ///
+    /// If `s` has more than `decimals` decimals, the result is rounded with `rounding`, or
+    /// `ParseError::ExcessPrecision` is returned if `rounding` is None.
+    fn parse_decimal(
+        env: &Env,
+        s: &str,
+        decimals: u32,
+        rounding: Option<Rounding>,
+    ) -> Result<Self, ParseError>;
+
+    /// Parses `s` into a fixed point number with `decimals` decimals.
+    ///
+    /// If `s` has more than `decimals` decimals, the result is rounded with `rounding`, or
+    /// `ParseError::ExcessPrecision` is returned if `rounding` is None.
+    fn parse_decimal_string(
+        s: &String,
+        decimals: u32,
+        rounding: Option<Rounding>,
+    ) -> Result<Self, ParseError>;
+}
+
+impl ParseDecimal for i64 {
+    fn parse_decimal(
+        env: &Env,
+        s: &str,
+        decimals: u32,
+        rounding: Option<Rounding>,
+    ) -> Result<i64, ParseError> {
+        parse(env, s.as_bytes(), decimals, rounding)
+    }
+
+    fn parse_decimal_string(
+        s: &String,
+        decimals: u32,
+        rounding: Option<Rounding>,
+    ) -> Result<i64, ParseError> {
+        parse_string(s, decimals, rounding)
+    }
+}
+
+impl ParseDecimal for u64 {
+    fn parse_decimal(
+        env: &Env,
+        s: &str,
+        decimals: u32,
+        rounding: Option<Rounding>,
+    ) -> Result<u64, ParseError> {
+        parse(env, s.as_bytes(), decimals, rounding)
+    }
+
+    fn parse_decimal_string(
+        s: &String,
+        decimals: u32,
+        rounding: Option<Rounding>,
+    ) -> Result<u64, ParseError> {
+        parse_string(s, decimals, rounding)
+    }
+}
+
+impl ParseDecimal for i128 {
+    fn parse_decimal(
+        env: &Env,
+        s: &str,
+        decimals: u32,
+        rounding: Option<Rounding>,
+    ) -> Result<i128, ParseError> {
+        parse(env, s.as_bytes(), decimals, rounding)
+    }
+
+    fn parse_decimal_string(
+        s: &String,
+        decimals: u32,
+        rounding: Option<Rounding>,
+    ) -> Result<i128, ParseError> {
+        parse_string(s, decimals, rounding)
+    }
+}
+
+impl ParseDecimal for u128 {
+    fn parse_decimal(
+        env: &Env,
+        s: &str,
+        decimals: u32,
+        rounding: Option<Rounding>,
+    ) -> Result<u128, ParseError> {
+        parse(env, s.as_bytes(), decimals, rounding)
+    }
+
+    fn parse_decimal_string(
+        s: &String,
+        decimals: u32,
+        rounding: Option<Rounding>,
+    ) -> Result<u128, ParseError> {
+        parse_string(s, decimals, rounding)
+    }
+}
+
+impl ParseDecimal for I256 {
+    fn parse_decimal(
+        env: &Env,
+        s: &str,
+        decimals: u32,
+        rounding: Option<Rounding>,
+    ) -> Result<I256, ParseError> {
+        parse(env, s.as_bytes(), decimals, rounding)
+    }
+
+    fn parse_decimal_string(
+        s: &String,
+        decimals: u32,
+        rounding: Option<Rounding>,
+    ) -> Result<I256, ParseError> {
+        parse_string(s, decimals, rounding)
+    }
+}
+
+impl ParseDecimal for U256 {
+    fn parse_decimal(
+        env: &Env,
+        s: &str,
+        decimals: u32,
+        rounding: Option<Rounding>,
+    ) -> Result<U256, ParseError> {
+        parse(env, s.as_bytes(), decimals, rounding)
+    }
+
+    fn parse_decimal_string(
+        s: &String,
+        decimals: u32,
+        rounding: Option<Rounding>,
+    ) -> Result<U256, ParseError> {
+        parse_string(s, decimals, rounding)
+    }
+}
+
+/// An integer that a decimal string can be accumulated into
+trait Accumulator: Sized {
+    fn zero(env: &Env) -> Self;
+
+    /// Performs x * m + c, or x * m - c if `negative`, or None if the result does not fit in Self
+    fn mul_add(&self, env: &Env, m: u64, c: u64, negative: bool) -> Option<Self>;
+}
+
+impl Accumulator for i64 {
+    fn zero(_env: &Env) -> i64 {
+        0
+    }
+
+    fn mul_add(&self, _env: &Env, m: u64, c: u64, negative: bool) -> Option<i64> {
+        // m and c may not fit in an i64, so compute with i128, which cannot overflow
+        let r = *self as i128 * m as i128;
+        let r = if negative {
+            r - c as i128
+        } else {
+            r + c as i128
+        };
+        i64::try_from(r).ok()
+    }
+}
+
+impl Accumulator for u64 {
+    fn zero(_env: &Env) -> u64 {
+        0
+    }
+
+    fn mul_add(&self, _env: &Env, m: u64, c: u64, negative: bool) -> Option<u64> {
+        let r = self.checked_mul(m)?;
+        if negative {
+            r.checked_sub(c)
+        } else {
+            r.checked_add(c)
+        }
+    }
+}
+
+impl Accumulator for i128 {
+    fn zero(_env: &Env) -> i128 {
+        0
+    }
+
+    fn mul_add(&self, _env: &Env, m: u64, c: u64, negative: bool) -> Option<i128> {
+        let r = self.checked_mul(m as i128)?;
+        if negative {
+            r.checked_sub(c as i128)
+        } else {
+            r.checked_add(c as i128)
+        }
+    }
+}
+
+impl Accumulator for u128 {
+    fn zero(_env: &Env) -> u128 {
+        0
+    }
+
+    fn mul_add(&self, _env: &Env, m: u64, c: u64, negative: bool) -> Option<u128> {
+        let r = self.checked_mul(m as u128)?;
+        if negative {
+            r.checked_sub(c as u128)
+        } else {
+            r.checked_add(c as u128)
+        }
+    }
+}
+
+impl Accumulator for I256 {
+    fn zero(env: &Env) -> I256 {
+        I256::from_i32(env, 0)
+    }
+
+    fn mul_add(&self, env: &Env, m: u64, c: u64, negative: bool) -> Option<I256> {
+        let m = I256::from_i128(env, m as i128);
+        let c = I256::from_i128(env, c as i128);
+        // division truncates toward zero, which is the floor of a positive bound and the ceiling
+        // of a negative bound
+        if negative {
+            let min = I256::from_parts(env, i64::MIN, 0, 0, 0);
+            if *self < min.add(&c).div(&m) {
+                return None;
+            }
+            Some(self.mul(&m).sub(&c))
+        } else {
+            let max = I256::from_parts(env, i64::MAX, u64::MAX, u64::MAX, u64::MAX);
+            if *self > max.sub(&c).div(&m) {
+                return None;
+            }
+            Some(self.mul(&m).add(&c))
+        }
+    }
+}
+
+impl Accumulator for U256 {
+    fn zero(env: &Env) -> U256 {
+        U256::from_u32(env, 0)
+    }
+
+    fn mul_add(&self, env: &Env, m: u64, c: u64, negative: bool) -> Option<U256> {
+        let zero = U256::from_u32(env, 0);
+        if negative {
+            // a negative value only accumulates zeros, so any non-zero digit is an overflow
+            return if *self == zero && c == 0 {
+                Some(zero)
+            } else {
+                None
+            };
+        }
+        let m = U256::from_u128(env, m as u128);
+        let c = U256::from_u128(env, c as u128);
+        let max = U256::from_parts(env, u64::MAX, u64::MAX, u64::MAX, u64::MAX);
+        if *self > max.sub(&c).div(&m) {
+            return None;
+        }
+        Some(self.mul(&m).add(&c))
+    }
+}
+
+/// Parses the bytes of `s` into the accumulator T
+fn parse_string<T: Accumulator>(
+    s: &String,
+    decimals: u32,
+    rounding: Option<Rounding>,
+) -> Result<T, ParseError> {
+    let len = s.len();
+    if len > MAX_PARSE_LEN {
+        return Err(ParseError::InvalidFormat);
+    }
+    let mut buf = [0u8; MAX_PARSE_LEN as usize];
+    let bytes = &mut buf[..len as usize];
+    s.copy_into_slice(bytes);
+    parse(s.env(), bytes, decimals, rounding)
+}
+
+/// Parses `s` into the accumulator T
+fn parse<T: Accumulator>(
+    env: &Env,
+    s: &[u8],
+    decimals: u32,
+    rounding: Option<Rounding>,
+) -> Result<T, ParseError> {
+    // split the string into the sign, mantissa, and exponent
+    let (negative, s) = match s.first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
+    };
+    let (mantissa, exponent) = match s.iter().position(|b| *b == b'e' || *b == b'E') {
+        Some(i) => (&s[..i], parse_exponent(&s[i + 1..])?),
+        None => (s, 0),
+    };
+
+    // validate the mantissa, and find the total and fractional number of digits
+    let mut digits: i64 = 0;
+    let mut fraction_digits: i64 = 0;
+    let mut has_point = false;
+    let mut is_zero = true;
+    for b in mantissa {
+        match b {
+            b'0'..=b'9' => {
+                digits += 1;
+                if has_point {
+                    fraction_digits += 1;
+                }
+                is_zero &= *b == b'0';
+            }
+            b'.' if !has_point => has_point = true,
+            _ => return Err(ParseError::InvalidFormat),
+        }
+    }
+    if digits == 0 {
+        return Err(ParseError::InvalidFormat);
+    }
+    if is_zero {
+        return Ok(T::zero(env));
+    }
+
+    // the result is the mantissa digits, as an integer, times 10^shift
+    let shift = exponent - fraction_digits + decimals as i64;
+    if shift > MAX_DIGITS as i64 {
+        return Err(ParseError::Overflow);
+    }
+    let kept_digits = digits + shift.min(0);
+
+    let mut result = T::zero(env);
+    let mut chunk: u64 = 0;
+    let mut chunk_len: u32 = 0;
+    let mut dropped_non_zero = false;
+    let mantissa_digits = mantissa.iter().filter(|b| **b != b'.');
+    for (index, b) in (0i64..).zip(mantissa_digits) {
+        let digit = (b - b'0') as u64;
+        if index < kept_digits {
+            chunk = chunk * 10 + digit;
+            chunk_len += 1;
+            if chunk_len as usize == CHUNK_DIGITS {
+                result = result
+                    .mul_add(env, 10u64.pow(chunk_len), chunk, negative)
+                    .ok_or(ParseError::Overflow)?;
+                chunk = 0;
+                chunk_len = 0;
+            }
+        } else {
+            dropped_non_zero |= digit != 0;
+        }
+    }
+    result = result
+        .mul_add(env, 10u64.pow(chunk_len), chunk, negative)
+        .ok_or(ParseError::Overflow)?;
+
+    // append any zeros implied by the exponent or decimals
+    let mut zeros = shift.max(0) as u32;
+    while zeros > 0 {
+        let len = zeros.min(CHUNK_DIGITS as u32);
+        result = result
+            .mul_add(env, 10u64.pow(len), 0, negative)
+            .ok_or(ParseError::Overflow)?;
+        zeros -= len;
+    }
+
+    // round away the dropped digits
+    if dropped_non_zero {
+        let away_from_zero = match rounding {
+            None => return Err(ParseError::ExcessPrecision),
+            Some(Rounding::Floor) => negative,
+            Some(Rounding::Ceil) => !negative,
+        };
+        if away_from_zero {
+            result = result
+                .mul_add(env, 1, 1, negative)
+                .ok_or(ParseError::Overflow)?;
+        }
+    }
+    Ok(result)
+}
+
+/// Parses the digits of an exponent, with an optional sign
+fn parse_exponent(s: &[u8]) -> Result<i64, ParseError> {
+    let (negative, s) = match s.first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
+    };
+    if s.is_empty() {
+        return Err(ParseError::InvalidFormat);
+    }
+    let mut exponent: i64 = 0;
+    for b in s {
+        if !b.is_ascii_digit() {
+            return Err(ParseError::InvalidFormat);
+        }
+        // saturate, as any exponent this large over or underflows every supported type
+        exponent = (exponent * 10 + (b - b'0') as i64).min(u32::MAX as i64);
+    }
+    Ok(if negative { -exponent } else { exponent })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /********** to_decimal_string **********/
+
+    #[test]
+    fn test_to_decimal_string_i128() {
+        let env = Env::default();
+
+        let result = (-123456789i128).to_decimal_string(&env, 7, TrailingZeros::Keep);
+
+        assert_eq!(result, String::from_str(&env, "-12.3456789"));
+    }
+
+    #[test]
+    fn test_to_decimal_string_leading_zeros() {
+        let env = Env::default();
+
+        let result = (-5i128).to_decimal_string(&env, 7, TrailingZeros::Keep);
+
+        assert_eq!(result, String::from_str(&env, "-0.0000005"));
+    }
+
+    #[test]
+    fn test_to_decimal_string_trailing_zeros() {
+        let env = Env::default();
+        let x: u128 = 1_5000000;
+
+        let keep = x.to_decimal_string(&env, 7, TrailingZeros::Keep);
+        let trim = x.to_decimal_string(&env, 7, TrailingZeros::Trim);
+
+        assert_eq!(keep, String::from_str(&env, "1.5000000"));
+        assert_eq!(trim, String::from_str(&env, "1.5"));
+    }
+
+    #[test]
+    fn test_to_decimal_string_trims_decimal_point() {
+        let env = Env::default();
+
+        let integer = 20_0000000u128.to_decimal_string(&env, 7, TrailingZeros::Trim);
+        let zero = 0i128.to_decimal_string(&env, 7, TrailingZeros::Trim);
+
+        assert_eq!(integer, String::from_str(&env, "20"));
+        assert_eq!(zero, String::from_str(&env, "0"));
+    }
+
+    #[test]
+    fn test_to_decimal_string_zero_decimals() {
+        let env = Env::default();
+
+        let result = (-42i128).to_decimal_string(&env, 0, TrailingZeros::Keep);
+
+        assert_eq!(result, String::from_str(&env, "-42"));
+    }
+
+    #[test]
+    fn test_to_decimal_string_native_bounds() {
+        let env = Env::default();
+
+        let min = i128::MIN.to_decimal_string(&env, 18, TrailingZeros::Keep);
+        let max = u128::MAX.to_decimal_string(&env, 38, TrailingZeros::Keep);
+
+        assert_eq!(
+            min,
+            String::from_str(&env, "-170141183460469231731.687303715884105728")
+        );
+        assert_eq!(
+            max,
+            String::from_str(&env, "3.40282366920938463463374607431768211455")
+        );
+    }
+
+    #[test]
+    fn test_to_decimal_string_i256() {
+        let env = Env::default();
+        let x = I256::from_i128(&env, -10i128.pow(20)).mul(&I256::from_i128(&env, 10i128.pow(20)));
+
+        let result =
+            x.add(&I256::from_i32(&env, -1))
+                .to_decimal_string(&env, 18, TrailingZeros::Keep);
+
+        assert_eq!(
+            result,
+            String::from_str(&env, "-10000000000000000000000.000000000000000001")
+        );
+    }
+
+    #[test]
+    fn test_to_decimal_string_i256_min() {
+        let env = Env::default();
+        let min = I256::from_parts(&env, i64::MIN, 0, 0, 0);
+
+        let result = min.to_decimal_string(&env, 0, TrailingZeros::Keep);
+
+        assert_eq!(
+            result,
+            String::from_str(
+                &env,
+                "-57896044618658097711785492504343953926634992332820282019728792003956564819968"
+            )
+        );
+    }
+
+    #[test]
+    fn test_to_decimal_string_u256_max() {
+        let env = Env::default();
+        let max = U256::from_parts(&env, u64::MAX, u64::MAX, u64::MAX, u64::MAX);
+
+        let result = max.to_decimal_string(&env, MAX_DECIMALS, TrailingZeros::Trim);
+
+        assert_eq!(
+            result,
+            String::from_str(
+                &env,
+                "1.15792089237316195423570985008687907853269984665640564039457584007913129639935"
+            )
+        );
+    }
+
+    #[test]
+    fn test_to_decimal_string_u256_chunk_padding() {
+        let env = Env::default();
+        let x = U256::from_u128(&env, CHUNK).mul(&U256::from_u128(&env, CHUNK));
+
+        let result = x.to_decimal_string(&env, 38, TrailingZeros::Trim);
+
+        assert_eq!(result, String::from_str(&env, "1"));
+    }
+
+    #[test]
+    #[should_panic(expected = "decimals too large")]
+    fn test_to_decimal_string_decimals_too_large() {
+        let env = Env::default();
+
+        1i128.to_decimal_string(&env, MAX_DECIMALS + 1, TrailingZeros::Keep);
+    }
+
+    /********** to_decimal_bytes **********/
+
+    #[test]
+    fn test_to_decimal_bytes() {
+        let env = Env::default();
+
+        let result = (-123456789i128).to_decimal_bytes(&env, 7, TrailingZeros::Trim);
+
+        assert_eq!(result, Bytes::from_slice(&env, b"-12.3456789"));
+    }
+
+    /********** parse_decimal **********/
+
+    #[test]
+    fn test_parse_decimal_i128() {
+        let env = Env::default();
+
+        assert_eq!(
+            i128::parse_decimal(&env, "-12.3456789", 7, None),
+            Ok(-123456789)
+        );
+        assert_eq!(i128::parse_decimal(&env, "+1.5", 7, None), Ok(1_5000000));
+        assert_eq!(i128::parse_decimal(&env, "42", 7, None), Ok(42_0000000));
+        assert_eq!(i128::parse_decimal(&env, ".5", 7, None), Ok(5000000));
+        assert_eq!(i128::parse_decimal(&env, "3.", 7, None), Ok(3_0000000));
+        assert_eq!(i128::parse_decimal(&env, "-0.000", 7, None), Ok(0));
+    }
+
+    #[test]
+    fn test_parse_decimal_scientific_notation() {
+        let env = Env::default();
+
+        assert_eq!(u64::parse_decimal(&env, "1.5e3", 7, None), Ok(1500_0000000));
+        assert_eq!(u64::parse_decimal(&env, "10E-8", 7, None), Ok(1));
+        assert_eq!(i64::parse_decimal(&env, "-2.5e+2", 0, None), Ok(-250));
+        assert_eq!(u128::parse_decimal(&env, "0e999999999999", 7, None), Ok(0));
+    }
+
+    #[test]
+    fn test_parse_decimal_excess_precision() {
+        let env = Env::default();
+
+        assert_eq!(
+            i128::parse_decimal(&env, "1.23456785", 7, None),
+            Err(ParseError::ExcessPrecision)
+        );
+        // trailing zeros are not excess precision
+        assert_eq!(
+            i128::parse_decimal(&env, "1.23456780000", 7, None),
+            Ok(1_2345678)
+        );
+    }
+
+    #[test]
+    fn test_parse_decimal_rounding() {
+        let env = Env::default();
+
+        let floor = i128::parse_decimal(&env, "1.23456785", 7, Some(Rounding::Floor));
+        let ceil = i128::parse_decimal(&env, "1.23456785", 7, Some(Rounding::Ceil));
+        let negative_floor = i128::parse_decimal(&env, "-1.23456785", 7, Some(Rounding::Floor));
+        let negative_ceil = i128::parse_decimal(&env, "-1.23456785", 7, Some(Rounding::Ceil));
+        let small_ceil = u64::parse_decimal(&env, "1e-30", 7, Some(Rounding::Ceil));
+
+        assert_eq!(floor, Ok(1_2345678));
+        assert_eq!(ceil, Ok(1_2345679));
+        assert_eq!(negative_floor, Ok(-1_2345679));
+        assert_eq!(negative_ceil, Ok(-1_2345678));
+        assert_eq!(small_ceil, Ok(1));
+    }
+
+    #[test]
+    fn test_parse_decimal_invalid_format() {
+        let env = Env::default();
+
+        for s in [
+            "", "-", ".", "1.2.3", "1,000", " 1", "1e", "1e+", "e5", "--1", "0x10",
+        ] {
+            assert_eq!(
+                i128::parse_decimal(&env, s, 7, None),
+                Err(ParseError::InvalidFormat),
+                "{}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_decimal_native_bounds() {
+        let env = Env::default();
+
+        assert_eq!(
+            i64::parse_decimal(&env, "-922337203685.4775808", 7, None),
+            Ok(i64::MIN)
+        );
+        assert_eq!(
+            i64::parse_decimal(&env, "922337203685.4775808", 7, None),
+            Err(ParseError::Overflow)
+        );
+        assert_eq!(
+            u64::parse_decimal(&env, "18446744073709551615", 0, None),
+            Ok(u64::MAX)
+        );
+        assert_eq!(
+            i128::parse_decimal(&env, "-170141183460469231731.687303715884105728", 18, None),
+            Ok(i128::MIN)
+        );
+        assert_eq!(
+            u128::parse_decimal(&env, "340282366920938463463374607431768211455", 0, None),
+            Ok(u128::MAX)
+        );
+        assert_eq!(
+            u128::parse_decimal(&env, "340282366920938463463374607431768211456", 0, None),
+            Err(ParseError::Overflow)
+        );
+        assert_eq!(
+            u128::parse_decimal(&env, "-1", 0, None),
+            Err(ParseError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_parse_decimal_rounding_overflow() {
+        let env = Env::default();
+
+        let result = u64::parse_decimal(&env, "18446744073709551615.1", 0, Some(Rounding::Ceil));
+
+        assert_eq!(result, Err(ParseError::Overflow));
+    }
+
+    #[test]
+    fn test_parse_decimal_i256_bounds() {
+        let env = Env::default();
+        let min = I256::from_parts(&env, i64::MIN, 0, 0, 0);
+        let max = I256::from_parts(&env, i64::MAX, u64::MAX, u64::MAX, u64::MAX);
+
+        let parsed_min = I256::parse_decimal(
+            &env,
+            "-57896044618658097711785492504343953926634992332820282019728792003956564819968",
+            0,
+            None,
+        );
+        let parsed_max = I256::parse_decimal(
+            &env,
+            "5.7896044618658097711785492504343953926634992332820282019728792003956564819967e76",
+            0,
+            None,
+        );
+        let overflow = I256::parse_decimal(
+            &env,
+            "57896044618658097711785492504343953926634992332820282019728792003956564819968",
+            0,
+            None,
+        );
+
+        assert_eq!(parsed_min, Ok(min));
+        assert_eq!(parsed_max, Ok(max));
+        assert_eq!(overflow, Err(ParseError::Overflow));
+    }
+
+    #[test]
+    fn test_parse_decimal_u256_round_trip() {
+        let env = Env::default();
+        let max = U256::from_parts(&env, u64::MAX, u64::MAX, u64::MAX, u64::MAX);
+        let s = max.to_decimal_string(&env, 18, TrailingZeros::Keep);
+
+        let result = U256::parse_decimal_string(&s, 18, None);
+
+        assert_eq!(result, Ok(max));
+        assert_eq!(
+            U256::parse_decimal(&env, "1e78", 0, None),
+            Err(ParseError::Overflow)
+        );
+    }
+
+    /********** parse_decimal_string **********/
+
+    #[test]
+    fn test_parse_decimal_string() {
+        let env = Env::default();
+        let s = String::from_str(&env, "-12.3456789");
+
+        let result = i128::parse_decimal_string(&s, 7, None);
+
+        assert_eq!(result, Ok(-123456789));
+    }
+
+    #[test]
+    fn test_parse_decimal_string_too_long() {
+        let env = Env::default();
+        let s = String::from_bytes(&env, &[b'1'; MAX_PARSE_LEN as usize + 1]);
+
+        let result = u128::parse_decimal_string(&s, 0, None);
+
+        assert_eq!(result, Err(ParseError::InvalidFormat));
+    }
+}
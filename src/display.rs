@@ -0,0 +1,219 @@
+use core::fmt;
+
+use soroban_sdk::unwrap::UnwrapOptimized;
+
+use crate::decimal::{Decimal, Digits, TrailingZeros, MAX_DECIMALS};
+
+/// The length of the longest formatted native value, 39 integer digits, a ".", and
+/// `MAX_DECIMALS` fractional digits
+const MAX_LEN: usize = 40 + MAX_DECIMALS as usize;
+
+/// Display adapter that formats a fixed point number with `decimals` decimals, such as
+/// "-12.3456789" for a value of -123456789 with 7 decimals.
+///
+/// Formatting does not allocate, so the adapter can be used in `no_std` panic messages. The
+/// precision, e.g. `{:.2}`, sets the number of fractional digits, which are truncated toward
+/// zero or padded with zeros, and is capped at `MAX_DECIMALS`. The precision truncates rather
+/// than rounds, so 0.129 formats as "0.12" with `{:.2}`. Width, fill, alignment, and sign flags
+/// behave as they do for integers.
+///
+/// `Debug` formats the same decimal form as `Display`, so `{:?}` in assertions shows the value
+/// instead of the raw scaled integer.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct DisplayFixed<T> {
+    value: T,
+    decimals: u32,
+}
+
+impl<T> DisplayFixed<T> {
+    /// Creates an adapter for `value` with `decimals` decimals, or None if `decimals` is greater
+    /// than `MAX_DECIMALS`.
+    pub fn new(value: T, decimals: u32) -> Option<Self> {
+        if decimals > MAX_DECIMALS {
+            return None;
+        }
+        Some(DisplayFixed { value, decimals })
+    }
+
+    /// The fixed point value
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// The number of decimals of the value
+    pub fn decimals(&self) -> u32 {
+        self.decimals
+    }
+}
+
+impl fmt::Display for DisplayFixed<i64> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_fixed(
+            f,
+            self.value < 0,
+            self.value.unsigned_abs() as u128,
+            self.decimals,
+        )
+    }
+}
+
+impl fmt::Display for DisplayFixed<u64> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_fixed(f, false, self.value as u128, self.decimals)
+    }
+}
+
+impl fmt::Display for DisplayFixed<i128> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_fixed(f, self.value < 0, self.value.unsigned_abs(), self.decimals)
+    }
+}
+
+impl fmt::Display for DisplayFixed<u128> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_fixed(f, false, self.value, self.decimals)
+    }
+}
+
+impl<T> fmt::Debug for DisplayFixed<T>
+where
+    DisplayFixed<T>: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Writes the magnitude of a fixed point number with `decimals` decimals, padded by `f`. The
+/// decimals are at most `MAX_DECIMALS`, as checked by `DisplayFixed::new`.
+fn fmt_fixed(
+    f: &mut fmt::Formatter<'_>,
+    negative: bool,
+    magnitude: u128,
+    decimals: u32,
+) -> fmt::Result {
+    // the sign is written by `pad_integral`
+    let digits = Digits::from_u128(magnitude);
+    let decimal = Decimal::new(false, &digits, decimals, TrailingZeros::Keep);
+    let formatted = decimal.as_bytes();
+
+    let mut buf = [b'0'; MAX_LEN];
+    let len = match f.precision() {
+        None => {
+            buf[..formatted.len()].copy_from_slice(formatted);
+            formatted.len()
+        }
+        Some(precision) => {
+            let precision = precision.min(MAX_DECIMALS as usize);
+            let integer_len = formatted.len()
+                - if decimals > 0 {
+                    decimals as usize + 1
+                } else {
+                    0
+                };
+            let kept_len = formatted.len().min(integer_len + 1 + precision);
+            buf[..kept_len].copy_from_slice(&formatted[..kept_len]);
+            if precision == 0 {
+                integer_len
+            } else {
+                // the buffer is initialized with zeros, so only a missing "." needs to be added
+                buf[integer_len] = b'.';
+                integer_len + 1 + precision
+            }
+        }
+    };
+    // the buffer only holds ASCII digits and a "."
+    let body = core::str::from_utf8(&buf[..len]).unwrap_optimized();
+    // a value that is zero after truncation is never negative
+    let is_zero = body.bytes().all(|b| b == b'0' || b == b'.');
+    f.pad_integral(!negative || is_zero, "", body)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::format;
+
+    #[test]
+    fn test_debug_fixed() {
+        let value = DisplayFixed::new(-123456789i128, 7).unwrap();
+
+        assert_eq!(format!("{:?}", value), "-12.3456789");
+        assert_eq!(format!("{:.2?}", value), "-12.34");
+        assert_eq!(
+            format!("{:?}", Some(DisplayFixed::new(5u64, 2).unwrap())),
+            "Some(0.05)"
+        );
+    }
+
+    #[test]
+    fn test_display_fixed() {
+        assert_eq!(
+            format!("{}", DisplayFixed::new(-123456789i128, 7).unwrap()),
+            "-12.3456789"
+        );
+        assert_eq!(
+            format!("{}", DisplayFixed::new(5u64, 7).unwrap()),
+            "0.0000005"
+        );
+        assert_eq!(format!("{}", DisplayFixed::new(-42i64, 0).unwrap()), "-42");
+        assert_eq!(format!("{}", DisplayFixed::new(0u128, 2).unwrap()), "0.00");
+    }
+
+    #[test]
+    fn test_display_fixed_bounds() {
+        assert_eq!(
+            format!("{}", DisplayFixed::new(i128::MIN, 18).unwrap()),
+            "-170141183460469231731.687303715884105728"
+        );
+        assert_eq!(
+            format!("{}", DisplayFixed::new(u64::MAX, 19).unwrap()),
+            "1.8446744073709551615"
+        );
+    }
+
+    #[test]
+    fn test_display_fixed_precision_truncates() {
+        let x = DisplayFixed::new(-1_2345678i128, 7).unwrap();
+
+        assert_eq!(format!("{:.2}", x), "-1.23");
+        assert_eq!(format!("{:.0}", x), "-1");
+        assert_eq!(format!("{:.0}", DisplayFixed::new(-5i64, 1).unwrap()), "0");
+        // 0.129 is not rounded up
+        assert_eq!(
+            format!("{:.2}", DisplayFixed::new(129u128, 3).unwrap()),
+            "0.12"
+        );
+    }
+
+    #[test]
+    fn test_display_fixed_precision_pads() {
+        let x = DisplayFixed::new(1_5u128, 1).unwrap();
+
+        assert_eq!(format!("{:.4}", x), "1.5000");
+        assert_eq!(
+            format!("{:.2}", DisplayFixed::new(7u64, 0).unwrap()),
+            "7.00"
+        );
+    }
+
+    #[test]
+    fn test_display_fixed_width_and_sign() {
+        let x = DisplayFixed::new(1_5000000i128, 7).unwrap();
+
+        assert_eq!(format!("{:>8.2}", x), "    1.50");
+        assert_eq!(
+            format!("{:08.2}", DisplayFixed::new(-1_5000000i128, 7).unwrap()),
+            "-0001.50"
+        );
+        assert_eq!(format!("{:+.1}", x), "+1.5");
+    }
+
+    #[test]
+    fn test_display_fixed_decimals_too_large() {
+        assert_eq!(DisplayFixed::new(1i128, MAX_DECIMALS + 1), None);
+        assert!(DisplayFixed::new(1i128, MAX_DECIMALS).is_some());
+    }
+}
@@ -1,3 +1,5 @@
+use core::ops::Sub;
+
 /// Fixed point trait for computing fixed point calculations with native rust types.
 pub trait FixedPoint: Sized {
     /// Safely calculates floor(x * y / denominator). Returns None if a phantom overflow
@@ -15,4 +17,38 @@ pub trait FixedPoint: Sized {
     /// Safely calculates ceil(x * denominator / y). Returns None if a phantom overflow
     /// occurs or if the denominator is 0.
     fn fixed_div_ceil(self, y: Self, denominator: Self) -> Option<Self>;
+
+    /// Safely calculates the smallest gross amount that leaves at least x after a fee of
+    /// `fee_rate` is taken, or ceil(x * scale / (scale - fee_rate)). Returns None if a phantom
+    /// overflow occurs or if the fee rate is not within [0, scale).
+    ///
+    /// This is the exact inverse of `net_down`, such that `x.gross_up(f, s)?.net_down(f, s)`
+    /// always returns x for a non-negative x.
+    fn gross_up(self, fee_rate: Self, scale: Self) -> Option<Self>
+    where
+        Self: Copy + Default + PartialOrd + Sub<Output = Self>,
+    {
+        self.fixed_mul_ceil(scale, fee_complement(fee_rate, scale)?)
+    }
+
+    /// Safely calculates the amount remaining after a fee of `fee_rate` is taken from x, or
+    /// floor(x * (scale - fee_rate) / scale). The fee taken, `x - net`, is rounded up. Returns
+    /// None if a phantom overflow occurs or if the fee rate is not within [0, scale).
+    fn net_down(self, fee_rate: Self, scale: Self) -> Option<Self>
+    where
+        Self: Copy + Default + PartialOrd + Sub<Output = Self>,
+    {
+        self.fixed_mul_floor(fee_complement(fee_rate, scale)?, scale)
+    }
+}
+
+/// Performs scale - fee_rate, or None if fee_rate is not within [0, scale)
+pub(crate) fn fee_complement<T>(fee_rate: T, scale: T) -> Option<T>
+where
+    T: Copy + Default + PartialOrd + Sub<Output = T>,
+{
+    if fee_rate < T::default() || fee_rate >= scale {
+        return None;
+    }
+    Some(scale - fee_rate)
 }
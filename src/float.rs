@@ -0,0 +1,356 @@
+use soroban_sdk::unwrap::UnwrapOptimized;
+
+use crate::{decimal::Digits, Rounding};
+
+/// The maximum number of decimals supported when converting from an f64, such that
+/// 10^decimals fits in a u128
+pub const MAX_DECIMALS: u32 = 38;
+
+/// The length of the longest scientific notation string, such as "-" followed by 39 digits
+/// and "e-4294967295"
+const MAX_LEN: usize = 52;
+
+// @dev - conversions are exact up to the final rounding step, and no floating point arithmetic is
+// performed.
+//
+// `to_f64` formats the value in scientific notation, such as "-123456789e-7", and relies on the correctly
+// rounded float parsing in `core`. `from_f64` decomposes the float into mantissa * 2^exponent, and performs
+// mantissa * 10^decimals * 2^exponent with 192 bit integer math.
+//
+// Precision:
+// * `to_f64` is correctly rounded to the nearest f64, with ties to even
+// * `from_f64` is exact, or rounded once in the requested direction
+
+/// Errors that can occur while converting an f64 into a fixed point number
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FloatError {
+    /// The float is NaN or infinite
+    NotFinite,
+    /// The float has more decimals than requested, and no rounding was chosen
+    Inexact,
+    /// The value does not fit in the target type, or `decimals` is greater than `MAX_DECIMALS`
+    Overflow,
+}
+
+/// Float conversion trait for comparing fixed point numbers against floating point models
+/// off-chain. Floats are not deterministic across platforms, and should not be used in contracts.
+pub trait FloatConversion: Sized {
+    /// Converts x, a fixed point number with `decimals` decimals, into the nearest f64
+    fn to_f64(&self, decimals: u32) -> f64;
+
+    /// Converts x, a fixed point number with `decimals` decimals, into an f64, or None if the
+    /// f64 does not exactly represent x
+    fn to_f64_exact(&self, decimals: u32) -> Option<f64>;
+
+    /// Converts `x` into a fixed point number with `decimals` decimals.
+    ///
+    /// If `x` has more than `decimals` decimals, the result is rounded with `rounding`, or
+    /// `FloatError::Inexact` is returned if `rounding` is None.
+    fn from_f64(x: f64, decimals: u32, rounding: Option<Rounding>) -> Result<Self, FloatError>;
+}
+
+impl FloatConversion for i64 {
+    fn to_f64(&self, decimals: u32) -> f64 {
+        to_f64(*self < 0, self.unsigned_abs() as u128, decimals)
+    }
+
+    fn to_f64_exact(&self, decimals: u32) -> Option<f64> {
+        let r = self.to_f64(decimals);
+        (i64::from_f64(r, decimals, None) == Ok(*self)).then_some(r)
+    }
+
+    fn from_f64(x: f64, decimals: u32, rounding: Option<Rounding>) -> Result<i64, FloatError> {
+        let (negative, magnitude) = from_f64(x, decimals, rounding)?;
+        let r = to_signed(negative, magnitude)?;
+        i64::try_from(r).map_err(|_| FloatError::Overflow)
+    }
+}
+
+impl FloatConversion for u64 {
+    fn to_f64(&self, decimals: u32) -> f64 {
+        to_f64(false, *self as u128, decimals)
+    }
+
+    fn to_f64_exact(&self, decimals: u32) -> Option<f64> {
+        let r = self.to_f64(decimals);
+        (u64::from_f64(r, decimals, None) == Ok(*self)).then_some(r)
+    }
+
+    fn from_f64(x: f64, decimals: u32, rounding: Option<Rounding>) -> Result<u64, FloatError> {
+        let (negative, magnitude) = from_f64(x, decimals, rounding)?;
+        let r = to_unsigned(negative, magnitude)?;
+        u64::try_from(r).map_err(|_| FloatError::Overflow)
+    }
+}
+
+impl FloatConversion for i128 {
+    fn to_f64(&self, decimals: u32) -> f64 {
+        to_f64(*self < 0, self.unsigned_abs(), decimals)
+    }
+
+    fn to_f64_exact(&self, decimals: u32) -> Option<f64> {
+        let r = self.to_f64(decimals);
+        (i128::from_f64(r, decimals, None) == Ok(*self)).then_some(r)
+    }
+
+    fn from_f64(x: f64, decimals: u32, rounding: Option<Rounding>) -> Result<i128, FloatError> {
+        let (negative, magnitude) = from_f64(x, decimals, rounding)?;
+        to_signed(negative, magnitude)
+    }
+}
+
+impl FloatConversion for u128 {
+    fn to_f64(&self, decimals: u32) -> f64 {
+        to_f64(false, *self, decimals)
+    }
+
+    fn to_f64_exact(&self, decimals: u32) -> Option<f64> {
+        let r = self.to_f64(decimals);
+        (u128::from_f64(r, decimals, None) == Ok(*self)).then_some(r)
+    }
+
+    fn from_f64(x: f64, decimals: u32, rounding: Option<Rounding>) -> Result<u128, FloatError> {
+        let (negative, magnitude) = from_f64(x, decimals, rounding)?;
+        to_unsigned(negative, magnitude)
+    }
+}
+
+/// Performs magnitude / 10^decimals, with the sign of `negative`, rounded to the nearest f64
+fn to_f64(negative: bool, magnitude: u128, decimals: u32) -> f64 {
+    let mut buf = [0u8; MAX_LEN];
+    let mut len = 0;
+    let mut push = |bytes: &[u8]| {
+        buf[len..len + bytes.len()].copy_from_slice(bytes);
+        len += bytes.len();
+    };
+    if negative {
+        push(b"-");
+    }
+    push(Digits::from_u128(magnitude).as_bytes());
+    if decimals > 0 {
+        push(b"e-");
+        push(Digits::from_u128(decimals as u128).as_bytes());
+    }
+    // the buffer is always a valid ASCII float, so neither step can fail
+    core::str::from_utf8(&buf[..len])
+        .unwrap_optimized()
+        .parse::<f64>()
+        .unwrap_optimized()
+}
+
+/// Performs |x| * 10^decimals, returning the sign of x and the magnitude rounded with `rounding`
+fn from_f64(x: f64, decimals: u32, rounding: Option<Rounding>) -> Result<(bool, u128), FloatError> {
+    if !x.is_finite() {
+        return Err(FloatError::NotFinite);
+    }
+    let negative = x.is_sign_negative();
+    // x = mantissa * 2^exponent
+    let bits = x.to_bits();
+    let biased_exponent = ((bits >> 52) & 0x7ff) as i32;
+    let fraction = bits & ((1 << 52) - 1);
+    let (mantissa, exponent) = if biased_exponent == 0 {
+        (fraction, -1074)
+    } else {
+        (fraction | (1 << 52), biased_exponent - 1075)
+    };
+    if mantissa == 0 {
+        return Ok((negative, 0));
+    }
+    let scalar = 10u128.checked_pow(decimals).ok_or(FloatError::Overflow)?;
+    let (hi, lo) = mul_wide(mantissa, scalar);
+
+    if exponent >= 0 {
+        let shift = exponent as u32;
+        if hi != 0 || lo.leading_zeros() < shift {
+            return Err(FloatError::Overflow);
+        }
+        return Ok((negative, lo << shift));
+    }
+    let (quotient, inexact) = shr_wide(hi, lo, exponent.unsigned_abs())?;
+    if !inexact {
+        return Ok((negative, quotient));
+    }
+    let away_from_zero = match rounding {
+        None => return Err(FloatError::Inexact),
+        Some(Rounding::Floor) => negative,
+        Some(Rounding::Ceil) => !negative,
+    };
+    if away_from_zero {
+        let r = quotient.checked_add(1).ok_or(FloatError::Overflow)?;
+        Ok((negative, r))
+    } else {
+        Ok((negative, quotient))
+    }
+}
+
+/// Performs x * y, returning the high and low 128 bits of the 192 bit product
+fn mul_wide(x: u64, y: u128) -> (u128, u128) {
+    let low_product = x as u128 * (y as u64) as u128;
+    let high_product = x as u128 * (y >> 64);
+    let lo = low_product.wrapping_add(high_product << 64);
+    let carry = (lo < low_product) as u128;
+    ((high_product >> 64) + carry, lo)
+}
+
+/// Performs (hi * 2^128 + lo) >> shift, returning the quotient and whether any non-zero bits
+/// were shifted out, or an error if the quotient does not fit in a u128
+fn shr_wide(hi: u128, lo: u128, shift: u32) -> Result<(u128, bool), FloatError> {
+    if shift >= 256 {
+        return Ok((0, hi != 0 || lo != 0));
+    }
+    if shift >= 128 {
+        let hi_shift = shift - 128;
+        let quotient = hi.checked_shr(hi_shift).unwrap_or(0);
+        let inexact = lo != 0 || quotient.checked_shl(hi_shift).unwrap_or(0) != hi;
+        return Ok((quotient, inexact));
+    }
+    // 0 < shift < 128, as shift is derived from a negative exponent
+    if hi >> shift != 0 {
+        return Err(FloatError::Overflow);
+    }
+    let quotient = (lo >> shift) | (hi << (128 - shift));
+    let inexact = (lo >> shift) << shift != lo;
+    Ok((quotient, inexact))
+}
+
+/// Applies the sign `negative` to `magnitude`, or an error if the result does not fit in an i128
+fn to_signed(negative: bool, magnitude: u128) -> Result<i128, FloatError> {
+    let r = if negative {
+        0i128.checked_sub_unsigned(magnitude)
+    } else {
+        i128::try_from(magnitude).ok()
+    };
+    r.ok_or(FloatError::Overflow)
+}
+
+/// Applies the sign `negative` to `magnitude`, or an error if the result is negative
+fn to_unsigned(negative: bool, magnitude: u128) -> Result<u128, FloatError> {
+    if negative && magnitude != 0 {
+        return Err(FloatError::Overflow);
+    }
+    Ok(magnitude)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /********** to_f64 **********/
+
+    #[test]
+    fn test_to_f64() {
+        assert_eq!(1_5000000i128.to_f64(7), 1.5);
+        assert_eq!((-123456789i64).to_f64(7), -12.3456789);
+        assert_eq!(1u64.to_f64(1), 0.1);
+        assert_eq!(42u128.to_f64(0), 42.0);
+        assert_eq!(0i128.to_f64(18), 0.0);
+    }
+
+    #[test]
+    fn test_to_f64_correctly_rounded() {
+        // 2^53 + 1 is not representable, and ties to even rounds down to 2^53
+        assert_eq!(9007199254740993u64.to_f64(0), 9007199254740992.0);
+        assert_eq!(1_234_567_890_123_456_789i128.to_f64(18), 1.2345678901234567);
+        assert_eq!(u128::MAX.to_f64(38), 3.4028236692093845);
+        assert_eq!(1u128.to_f64(400), 0.0);
+    }
+
+    #[test]
+    fn test_to_f64_exact() {
+        assert_eq!(1_5000000i128.to_f64_exact(7), Some(1.5));
+        assert_eq!((-25i64).to_f64_exact(2), Some(-0.25));
+        assert_eq!(1u64.to_f64_exact(1), None);
+        assert_eq!(9007199254740993u128.to_f64_exact(0), None);
+        assert_eq!(i128::MIN.to_f64_exact(0), Some(-((1u128 << 127) as f64)));
+    }
+
+    /********** from_f64 **********/
+
+    #[test]
+    fn test_from_f64() {
+        assert_eq!(i128::from_f64(1.5, 7, None), Ok(1_5000000));
+        assert_eq!(i64::from_f64(-0.25, 2, None), Ok(-25));
+        assert_eq!(u64::from_f64(1e10, 0, None), Ok(10_000_000_000));
+        assert_eq!(u128::from_f64(-0.0, 7, None), Ok(0));
+        assert_eq!(
+            i128::from_f64(f64::from_bits(1), 0, Some(Rounding::Floor)),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn test_from_f64_rounding() {
+        // 0.1 is slightly above 1/10 as an f64
+        assert_eq!(i128::from_f64(0.1, 1, None), Err(FloatError::Inexact));
+        assert_eq!(i128::from_f64(0.1, 1, Some(Rounding::Floor)), Ok(1));
+        assert_eq!(i128::from_f64(0.1, 1, Some(Rounding::Ceil)), Ok(2));
+        assert_eq!(i128::from_f64(-0.1, 1, Some(Rounding::Floor)), Ok(-2));
+        assert_eq!(i128::from_f64(-0.1, 1, Some(Rounding::Ceil)), Ok(-1));
+        assert_eq!(u64::from_f64(-0.1, 0, Some(Rounding::Ceil)), Ok(0));
+
+        // 1.23456785 is slightly below its decimal value as an f64
+        assert_eq!(
+            i64::from_f64(1.23456785, 7, Some(Rounding::Ceil)),
+            Ok(1_2345679)
+        );
+        assert_eq!(
+            i64::from_f64(1.23456785, 7, Some(Rounding::Floor)),
+            Ok(1_2345678)
+        );
+    }
+
+    #[test]
+    fn test_from_f64_large_decimals() {
+        let r = u128::from_f64(1.0, MAX_DECIMALS, None);
+
+        assert_eq!(r, Ok(10u128.pow(MAX_DECIMALS)));
+        assert_eq!(
+            u128::from_f64(1.0, MAX_DECIMALS + 1, None),
+            Err(FloatError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_from_f64_bounds() {
+        let min = -((1u128 << 127) as f64);
+
+        assert_eq!(i128::from_f64(min, 0, None), Ok(i128::MIN));
+        assert_eq!(i128::from_f64(-min, 0, None), Err(FloatError::Overflow));
+        assert_eq!(u128::from_f64(-min, 0, None), Ok(1 << 127));
+        assert_eq!(
+            u128::from_f64(u128::MAX as f64, 0, None),
+            Err(FloatError::Overflow)
+        );
+        assert_eq!(i64::from_f64(i64::MIN as f64, 0, None), Ok(i64::MIN));
+        assert_eq!(
+            i64::from_f64(-(i64::MIN as f64), 0, None),
+            Err(FloatError::Overflow)
+        );
+        assert_eq!(u64::from_f64(-1.0, 0, None), Err(FloatError::Overflow));
+        assert_eq!(i128::from_f64(1e30, 18, None), Err(FloatError::Overflow));
+    }
+
+    #[test]
+    fn test_from_f64_not_finite() {
+        assert_eq!(
+            i128::from_f64(f64::NAN, 7, None),
+            Err(FloatError::NotFinite)
+        );
+        assert_eq!(
+            u64::from_f64(f64::INFINITY, 7, None),
+            Err(FloatError::NotFinite)
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for x in [1i128, -1, 1_2345678, -9_8765432_1234567, (1 << 53) - 1] {
+            let f = x.to_f64(7);
+            let floor = i128::from_f64(f, 7, Some(Rounding::Floor)).unwrap();
+            let ceil = i128::from_f64(f, 7, Some(Rounding::Ceil)).unwrap();
+
+            assert!(floor == x || ceil == x);
+            assert!(ceil - floor <= 1);
+        }
+    }
+}
@@ -1,6 +1,9 @@
-use soroban_sdk::{unwrap::UnwrapOptimized, Env, I256};
+use soroban_sdk::{unwrap::UnwrapOptimized, Env};
 
-use crate::{fixed_point::FixedPoint, SorobanFixedPoint};
+use crate::{
+    fixed_point::{fee_complement, FixedPoint},
+    SorobanFixedPoint, SorobanFixedPointExt,
+};
 
 impl FixedPoint for i128 {
     fn fixed_mul_floor(self, y: i128, denominator: i128) -> Option<i128> {
@@ -28,7 +31,7 @@ pub(crate) fn mul_div_floor(x: i128, y: i128, z: i128) -> Option<i128> {
 
 /// Performs floor(r / z)
 fn div_floor(r: i128, z: i128) -> Option<i128> {
-    if r < 0 || (r > 0 && z < 0) {
+    if (r < 0 && z > 0) || (r > 0 && z < 0) {
         // ceiling is taken by default for a negative result
         let remainder = r.checked_rem_euclid(z)?;
         (r / z).checked_sub(if remainder > 0 { 1 } else { 0 })
@@ -46,7 +49,7 @@ pub(crate) fn mul_div_ceil(x: i128, y: i128, z: i128) -> Option<i128> {
 
 /// Performs ceil(r / z)
 fn div_ceil(r: i128, z: i128) -> Option<i128> {
-    if r <= 0 || (r > 0 && z < 0) {
+    if (r <= 0 && z > 0) || (r >= 0 && z < 0) {
         // ceiling is taken by default for a negative or zero result
         r.checked_div(z)
     } else {
@@ -58,56 +61,160 @@ fn div_ceil(r: i128, z: i128) -> Option<i128> {
 
 impl SorobanFixedPoint for i128 {
     fn fixed_mul_floor(&self, env: &Env, y: &i128, denominator: &i128) -> i128 {
-        scaled_mul_div_floor(&self, env, y, denominator)
+        scaled_mul_div_floor(self, env, y, denominator)
     }
 
     fn fixed_mul_ceil(&self, env: &Env, y: &i128, denominator: &i128) -> i128 {
-        scaled_mul_div_ceil(&self, env, y, denominator)
+        scaled_mul_div_ceil(self, env, y, denominator)
     }
 
     fn fixed_div_floor(&self, env: &Env, y: &i128, denominator: &i128) -> i128 {
-        scaled_mul_div_floor(&self, env, denominator, y)
+        scaled_mul_div_floor(self, env, denominator, y)
     }
 
     fn fixed_div_ceil(&self, env: &Env, y: &i128, denominator: &i128) -> i128 {
-        scaled_mul_div_ceil(&self, env, denominator, y)
+        scaled_mul_div_ceil(self, env, denominator, y)
+    }
+}
+
+impl SorobanFixedPointExt for i128 {
+    fn gross_up(&self, env: &Env, fee_rate: &i128, scale: &i128) -> i128 {
+        let complement = fee_complement(*fee_rate, *scale).unwrap_optimized();
+        scaled_mul_div_ceil(self, env, scale, &complement)
+    }
+
+    fn net_down(&self, env: &Env, fee_rate: &i128, scale: &i128) -> i128 {
+        let complement = fee_complement(*fee_rate, *scale).unwrap_optimized();
+        scaled_mul_div_floor(self, env, &complement, scale)
+    }
+
+    fn fixed_mul_shr_floor(&self, env: &Env, y: &i128, shift: u32) -> i128 {
+        scaled_mul_shr_floor(self, env, y, shift)
+    }
+
+    fn fixed_mul_shr_ceil(&self, env: &Env, y: &i128, shift: u32) -> i128 {
+        scaled_mul_shr_ceil(self, env, y, shift)
+    }
+
+    fn fixed_div_shl_floor(&self, env: &Env, y: &i128, shift: u32) -> i128 {
+        scaled_shl_div_floor(self, env, y, shift)
+    }
+
+    fn fixed_div_shl_ceil(&self, env: &Env, y: &i128, shift: u32) -> i128 {
+        scaled_shl_div_ceil(self, env, y, shift)
     }
 }
 
 /// Performs floor(x * y / z)
 fn scaled_mul_div_floor(x: &i128, env: &Env, y: &i128, z: &i128) -> i128 {
-    return match x.checked_mul(*y) {
+    match x.checked_mul(*y) {
         Some(r) => div_floor(r, *z).unwrap_optimized(),
         None => {
-            // scale to i256 and retry
-            let res = crate::i256::mul_div_floor(
-                &env,
-                &I256::from_i128(&env, *x),
-                &I256::from_i128(&env, *y),
-                &I256::from_i128(&env, *z),
-            );
-            // will panic if result is not representable in i128
-            res.to_i128().unwrap_optimized()
+            let (quotient, exact) = scaled_mul_div_trunc(*x, env, *y, *z);
+            // the quotient is truncated toward zero, so only an inexact negative result is adjusted
+            if !exact && (*x < 0) ^ (*y < 0) ^ (*z < 0) {
+                quotient.checked_sub(1).unwrap_optimized()
+            } else {
+                quotient
+            }
         }
-    };
+    }
 }
 
 /// Performs floor(x * y / z)
 fn scaled_mul_div_ceil(x: &i128, env: &Env, y: &i128, z: &i128) -> i128 {
-    return match x.checked_mul(*y) {
+    match x.checked_mul(*y) {
         Some(r) => div_ceil(r, *z).unwrap_optimized(),
         None => {
+            let (quotient, exact) = scaled_mul_div_trunc(*x, env, *y, *z);
+            // the quotient is truncated toward zero, so only an inexact positive result is adjusted
+            if !exact && !((*x < 0) ^ (*y < 0) ^ (*z < 0)) {
+                quotient.checked_add(1).unwrap_optimized()
+            } else {
+                quotient
+            }
+        }
+    }
+}
+
+/// Performs x * y / z truncated toward zero with I256, for a product that does not fit in an
+/// i128. Returns the quotient and whether the division was exact.
+///
+/// ### Panics
+/// If z is 0 or the quotient does not fit in an i128
+fn scaled_mul_div_trunc(x: i128, env: &Env, y: i128, z: i128) -> (i128, bool) {
+    let quotient = crate::i256::from_i128(env, x)
+        .mul(&crate::i256::from_i128(env, y))
+        .div(&crate::i256::from_i128(env, z));
+    // the truncated quotient is never further from zero than the rounded result, so it fits
+    // whenever the result does
+    let quotient = crate::i256::to_i128(&quotient).unwrap_optimized();
+    // the remainder is smaller than |z| <= 2^127, so the division is exact if and only if the low
+    // 128 bits of x * y and quotient * z match
+    (quotient, x.wrapping_mul(y) == quotient.wrapping_mul(z))
+}
+
+/// Performs floor(x * y / 2^shift)
+fn scaled_mul_shr_floor(x: &i128, env: &Env, y: &i128, shift: u32) -> i128 {
+    match x.checked_mul(*y) {
+        // an arithmetic shift takes the floor for a negative result
+        Some(r) if shift < 128 => r >> shift,
+        _ => {
             // scale to i256 and retry
-            let res = crate::i256::mul_div_ceil(
-                &env,
-                &I256::from_i128(&env, *x),
-                &I256::from_i128(&env, *y),
-                &I256::from_i128(&env, *z),
+            let res = crate::i256::mul_shr_floor(
+                &crate::i256::from_i128(env, *x),
+                &crate::i256::from_i128(env, *y),
+                shift,
             );
             // will panic if result is not representable in i128
-            res.to_i128().unwrap_optimized()
+            crate::i256::to_i128(&res).unwrap_optimized()
         }
-    };
+    }
+}
+
+/// Performs ceil(x * y / 2^shift)
+fn scaled_mul_shr_ceil(x: &i128, env: &Env, y: &i128, shift: u32) -> i128 {
+    match x.checked_mul(*y) {
+        // any bits dropped by the shift are the remainder
+        Some(r) if shift < 128 => (r >> shift) + ((r >> shift) << shift != r) as i128,
+        _ => {
+            // scale to i256 and retry
+            let res = crate::i256::mul_shr_ceil(
+                env,
+                &crate::i256::from_i128(env, *x),
+                &crate::i256::from_i128(env, *y),
+                shift,
+            );
+            // will panic if result is not representable in i128
+            crate::i256::to_i128(&res).unwrap_optimized()
+        }
+    }
+}
+
+/// Performs floor(x * 2^shift / y)
+fn scaled_shl_div_floor(x: &i128, env: &Env, y: &i128, shift: u32) -> i128 {
+    if x.unsigned_abs().leading_zeros() > shift {
+        div_floor(x << shift, *y).unwrap_optimized()
+    } else {
+        // scale to i256 and retry
+        let r = crate::i256::shl_checked(&crate::i256::from_i128(env, *x), shift);
+        let res = crate::i256::div_floor(env, &r, &crate::i256::from_i128(env, *y));
+        // will panic if result is not representable in i128
+        crate::i256::to_i128(&res).unwrap_optimized()
+    }
+}
+
+/// Performs ceil(x * 2^shift / y)
+fn scaled_shl_div_ceil(x: &i128, env: &Env, y: &i128, shift: u32) -> i128 {
+    if x.unsigned_abs().leading_zeros() > shift {
+        div_ceil(x << shift, *y).unwrap_optimized()
+    } else {
+        // scale to i256 and retry
+        let r = crate::i256::shl_checked(&crate::i256::from_i128(env, *x), shift);
+        let res = crate::i256::div_ceil(env, &r, &crate::i256::from_i128(env, *y));
+        // will panic if result is not representable in i128
+        crate::i256::to_i128(&res).unwrap_optimized()
+    }
 }
 
 #[cfg(test)]
@@ -139,6 +246,17 @@ mod test_fixed_point {
         assert_eq!(result, -483_5313676)
     }
 
+    #[test]
+    fn test_fixed_mul_floor_both_negative_rounds_down() {
+        let x: i128 = -1_5391283;
+        let y: i128 = -314_1592653;
+        let denominator: i128 = 1_0000001;
+
+        let result = x.fixed_mul_floor(y, denominator).unwrap();
+
+        assert_eq!(result, 483_5313675)
+    }
+
     #[test]
     fn test_fixed_mul_floor_large_number() {
         let x: i128 = 170_141_183_460_469_231_731;
@@ -185,6 +303,17 @@ mod test_fixed_point {
         assert_eq!(result, -483_5313675)
     }
 
+    #[test]
+    fn test_fixed_mul_ceil_both_negative_rounds_up() {
+        let x: i128 = 1_5391283;
+        let y: i128 = -314_1592653;
+        let denominator: i128 = -1_0000001;
+
+        let result = x.fixed_mul_ceil(y, denominator).unwrap();
+
+        assert_eq!(result, 483_5313676)
+    }
+
     #[test]
     fn test_fixed_mul_ceil_large_number() {
         let x: i128 = 170_141_183_460_469_231_731;
@@ -298,11 +427,79 @@ mod test_fixed_point {
 
         assert_eq!(None, result);
     }
+
+    /********** gross_up **********/
+
+    #[test]
+    fn test_gross_up_rounds_up() {
+        // 12.34 tokens with 18 decimals, after a 0.3% fee
+        let net: i128 = 1234 * 10i128.pow(16);
+        let fee_rate: i128 = 3 * 10i128.pow(15);
+        let scale: i128 = 10i128.pow(18);
+
+        let result = net.gross_up(fee_rate, scale).unwrap();
+
+        assert_eq!(result, 12_377_131_394_182_547_643)
+    }
+
+    #[test]
+    fn test_gross_up_negative_rounds_up() {
+        let net: i128 = -1234 * 10i128.pow(16);
+        let fee_rate: i128 = 3 * 10i128.pow(15);
+        let scale: i128 = 10i128.pow(18);
+
+        let result = net.gross_up(fee_rate, scale).unwrap();
+
+        assert_eq!(result, -12_377_131_394_182_547_642)
+    }
+
+    #[test]
+    fn test_gross_up_phantom_overflow() {
+        // net * scale overflows, and the native implementation does not scale to 256 bits
+        let net: i128 = 10i128.pow(21);
+        let scale: i128 = 10i128.pow(18);
+
+        let result = net.gross_up(3 * 10i128.pow(15), scale);
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn test_gross_up_invalid_fee_rate() {
+        let scale: i128 = 10i128.pow(18);
+
+        assert_eq!(10i128.pow(18).gross_up(scale, scale), None);
+        assert_eq!(10i128.pow(18).gross_up(-1, scale), None);
+    }
+
+    /********** net_down **********/
+
+    #[test]
+    fn test_net_down_negative_rounds_down() {
+        let gross: i128 = -12_377_131_394_182_547_643;
+        let fee_rate: i128 = 3 * 10i128.pow(15);
+        let scale: i128 = 10i128.pow(18);
+
+        let result = gross.net_down(fee_rate, scale).unwrap();
+
+        // the fee taken, gross - net, is rounded up
+        assert_eq!(result, -12_340_000_000_000_000_001)
+    }
+
+    #[test]
+    fn test_net_down_zero_fee() {
+        let result = i128::MIN.net_down(0, 1);
+
+        assert_eq!(result, Some(i128::MIN))
+    }
 }
 
 #[cfg(test)]
 mod test_soroban_fixed_point {
-    use crate::SorobanFixedPoint;
+    use crate::{
+        budget::{baseline_i128_mul_div_ceil, baseline_i128_mul_div_floor, measure},
+        SorobanFixedPoint, SorobanFixedPointExt,
+    };
     use soroban_sdk::Env;
 
     /********** fixed_mul_floor **********/
@@ -331,6 +528,18 @@ mod test_soroban_fixed_point {
         assert_eq!(result, -483_5313676)
     }
 
+    #[test]
+    fn test_fixed_mul_floor_both_negative_rounds_down() {
+        let env = Env::default();
+        let x: i128 = -1_5391283;
+        let y: i128 = -314_1592653;
+        let denominator: i128 = 1_0000001;
+
+        let result = x.fixed_mul_floor(&env, &y, &denominator);
+
+        assert_eq!(result, 483_5313675)
+    }
+
     #[test]
     fn test_fixed_mul_floor_phantom_overflow_scales() {
         let env = Env::default();
@@ -369,6 +578,18 @@ mod test_soroban_fixed_point {
         assert_eq!(result, -483_5313675)
     }
 
+    #[test]
+    fn test_fixed_mul_ceil_both_negative_rounds_up() {
+        let env = Env::default();
+        let x: i128 = 1_5391283;
+        let y: i128 = -314_1592653;
+        let denominator: i128 = -1_0000001;
+
+        let result = x.fixed_mul_ceil(&env, &y, &denominator);
+
+        assert_eq!(result, 483_5313676)
+    }
+
     #[test]
     fn test_fixed_mul_ceil_large_number() {
         let env = Env::default();
@@ -480,4 +701,202 @@ mod test_soroban_fixed_point {
 
         assert_eq!(result, 170_141_183_460_469_231_731 * 10i128.pow(9));
     }
+
+    /********** gross_up **********/
+
+    #[test]
+    fn test_gross_up_rounds_up() {
+        let env = Env::default();
+        let net: i128 = 1_5391283;
+        let fee_rate: i128 = 30000;
+        let scale: i128 = 1_0000000;
+
+        let result = net.gross_up(&env, &fee_rate, &scale);
+
+        assert_eq!(result, 1_5437596);
+    }
+
+    #[test]
+    fn test_gross_up_is_inverse_of_net_down() {
+        let env = Env::default();
+        let net: i128 = 1_5391283;
+        let fee_rate: i128 = 30000;
+        let scale: i128 = 1_0000000;
+
+        let gross = net.gross_up(&env, &fee_rate, &scale);
+
+        assert_eq!(gross.net_down(&env, &fee_rate, &scale), net);
+    }
+
+    #[test]
+    fn test_gross_up_phantom_overflow_scales() {
+        let env = Env::default();
+        let net: i128 = 170_141_183_460_469_231_731;
+        let fee_rate: i128 = 5 * 10i128.pow(17);
+        let scale: i128 = 10i128.pow(18);
+
+        let result = net.gross_up(&env, &fee_rate, &scale);
+
+        assert_eq!(result, 340_282_366_920_938_463_462);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_gross_up_invalid_fee_rate() {
+        let env = Env::default();
+        let net: i128 = 1_5391283;
+        let scale: i128 = 1_0000000;
+
+        net.gross_up(&env, &scale, &scale);
+    }
+
+    /********** net_down **********/
+
+    #[test]
+    fn test_net_down_rounds_down() {
+        let env = Env::default();
+        let gross: i128 = 1_5437596;
+        let fee_rate: i128 = 30000;
+        let scale: i128 = 1_0000000;
+
+        let result = gross.net_down(&env, &fee_rate, &scale);
+
+        assert_eq!(result, 1_5391283);
+    }
+
+    /********** fixed_mul_shr_floor **********/
+
+    #[test]
+    fn test_fixed_mul_shr_floor_q64() {
+        let env = Env::default();
+        // 1.5 * 1.25 = 1.875
+        let x: i128 = 3 << 63;
+        let y: i128 = 5 << 62;
+
+        let result = x.fixed_mul_shr_floor(&env, &y, 64);
+
+        assert_eq!(result, 15 << 61);
+    }
+
+    #[test]
+    fn test_fixed_mul_shr_floor_negative_rounds_down() {
+        let env = Env::default();
+
+        let result = (-3i128).fixed_mul_shr_floor(&env, &1, 1);
+
+        assert_eq!(result, -2);
+    }
+
+    #[test]
+    fn test_fixed_mul_shr_floor_phantom_overflow_scales() {
+        let env = Env::default();
+        let x: i128 = i128::MIN;
+        let y: i128 = 1 << 64;
+
+        let result = x.fixed_mul_shr_floor(&env, &y, 64);
+
+        assert_eq!(result, i128::MIN);
+    }
+
+    /********** fixed_mul_shr_ceil **********/
+
+    #[test]
+    fn test_fixed_mul_shr_ceil_rounds_up() {
+        let env = Env::default();
+
+        assert_eq!(3i128.fixed_mul_shr_ceil(&env, &1, 1), 2);
+        assert_eq!((-3i128).fixed_mul_shr_ceil(&env, &1, 1), -1);
+        assert_eq!(4i128.fixed_mul_shr_ceil(&env, &1, 1), 2);
+    }
+
+    #[test]
+    fn test_fixed_mul_shr_ceil_phantom_overflow_scales() {
+        let env = Env::default();
+        let x: i128 = i128::MAX;
+        let y: i128 = 3;
+
+        let result = x.fixed_mul_shr_ceil(&env, &y, 2);
+
+        assert_eq!(result, (i128::MAX >> 2) * 3 + 3);
+    }
+
+    /********** fixed_div_shl_floor **********/
+
+    #[test]
+    fn test_fixed_div_shl_floor_rounds_down() {
+        let env = Env::default();
+        let x: i128 = 1 << 64;
+        let y: i128 = 3 << 64;
+
+        assert_eq!(x.fixed_div_shl_floor(&env, &y, 64), 6148914691236517205);
+        assert_eq!((-x).fixed_div_shl_floor(&env, &y, 64), -6148914691236517206);
+    }
+
+    #[test]
+    fn test_fixed_div_shl_floor_phantom_overflow_scales() {
+        let env = Env::default();
+        let x: i128 = 1 << 100;
+
+        let result = x.fixed_div_shl_floor(&env, &x, 64);
+
+        assert_eq!(result, 1 << 64);
+    }
+
+    /********** fixed_div_shl_ceil **********/
+
+    #[test]
+    fn test_fixed_div_shl_ceil_rounds_up() {
+        let env = Env::default();
+        let x: i128 = 1 << 64;
+        let y: i128 = 3 << 64;
+
+        assert_eq!(x.fixed_div_shl_ceil(&env, &y, 64), 6148914691236517206);
+        assert_eq!((-x).fixed_div_shl_ceil(&env, &y, 64), -6148914691236517205);
+    }
+
+    #[test]
+    fn test_fixed_div_shl_ceil_phantom_overflow_scales() {
+        let env = Env::default();
+        let x: i128 = -(1 << 100);
+        let y: i128 = 3 << 100;
+
+        let result = x.fixed_div_shl_ceil(&env, &y, 126);
+
+        assert_eq!(result, -(1i128 << 126) / 3);
+    }
+
+    /********** budget **********/
+
+    #[test]
+    fn test_fixed_mul_floor_phantom_overflow_budget() {
+        let env = Env::default();
+        let x: i128 = -(i128::MAX / 3);
+        let y: i128 = 1_000_000_000_000_000_000;
+        let denominator: i128 = 1_000_000_000_000_000_001;
+
+        let (result, cpu, mem) = measure(&env, || x.fixed_mul_floor(&env, &y, &denominator));
+        let (expected, baseline_cpu, baseline_mem) = measure(&env, || {
+            baseline_i128_mul_div_floor(&env, x, y, denominator)
+        });
+
+        assert_eq!(result, expected);
+        assert!(cpu < baseline_cpu);
+        assert!(mem < baseline_mem);
+    }
+
+    #[test]
+    fn test_fixed_mul_ceil_phantom_overflow_budget() {
+        let env = Env::default();
+        let x: i128 = i128::MAX / 3;
+        let y: i128 = 1_000_000_000_000_000_000;
+        let denominator: i128 = 1_000_000_000_000_000_001;
+
+        let (result, cpu, mem) = measure(&env, || x.fixed_mul_ceil(&env, &y, &denominator));
+        let (expected, baseline_cpu, baseline_mem) =
+            measure(&env, || baseline_i128_mul_div_ceil(&env, x, y, denominator));
+
+        assert_eq!(result, expected);
+        assert!(cpu < baseline_cpu);
+        assert!(mem < baseline_mem);
+    }
 }
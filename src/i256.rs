@@ -1,58 +1,171 @@
-use soroban_sdk::{Env, I256};
+use core::cmp::Ordering;
 
-use crate::soroban_fixed_point::SorobanFixedPoint;
+use soroban_sdk::{unwrap::UnwrapOptimized, Env, I256};
+
+use crate::soroban_fixed_point::{SorobanFixedPoint, SorobanFixedPointExt};
 
 impl SorobanFixedPoint for I256 {
     fn fixed_mul_floor(&self, env: &Env, y: &I256, denominator: &I256) -> I256 {
-        mul_div_floor(env, &self, y, denominator)
+        mul_div_floor(env, self, y, denominator)
     }
 
     fn fixed_mul_ceil(&self, env: &Env, y: &I256, denominator: &I256) -> I256 {
-        mul_div_ceil(env, &self, y, denominator)
+        mul_div_ceil(env, self, y, denominator)
     }
 
     fn fixed_div_floor(&self, env: &Env, y: &I256, denominator: &I256) -> I256 {
-        mul_div_floor(env, &self, denominator, y)
+        mul_div_floor(env, self, denominator, y)
     }
 
     fn fixed_div_ceil(&self, env: &Env, y: &I256, denominator: &I256) -> I256 {
-        mul_div_ceil(env, &self, denominator, y)
+        mul_div_ceil(env, self, denominator, y)
+    }
+}
+
+impl SorobanFixedPointExt for I256 {
+    fn gross_up(&self, env: &Env, fee_rate: &I256, scale: &I256) -> I256 {
+        let complement = fee_complement(env, fee_rate, scale);
+        mul_div_ceil(env, self, scale, &complement)
+    }
+
+    fn net_down(&self, env: &Env, fee_rate: &I256, scale: &I256) -> I256 {
+        let complement = fee_complement(env, fee_rate, scale);
+        mul_div_floor(env, self, &complement, scale)
+    }
+
+    fn fixed_mul_shr_floor(&self, _env: &Env, y: &I256, shift: u32) -> I256 {
+        mul_shr_floor(self, y, shift)
+    }
+
+    fn fixed_mul_shr_ceil(&self, env: &Env, y: &I256, shift: u32) -> I256 {
+        mul_shr_ceil(env, self, y, shift)
+    }
+
+    fn fixed_div_shl_floor(&self, env: &Env, y: &I256, shift: u32) -> I256 {
+        div_floor(env, &shl_checked(self, shift), y)
+    }
+
+    fn fixed_div_shl_ceil(&self, env: &Env, y: &I256, shift: u32) -> I256 {
+        div_ceil(env, &shl_checked(self, shift), y)
     }
 }
 
 /// Performs floor(x * y / z)
 pub(crate) fn mul_div_floor(env: &Env, x: &I256, y: &I256, z: &I256) -> I256 {
+    div_floor(env, &x.mul(y), z)
+}
+
+/// Performs floor(r / z)
+pub(crate) fn div_floor(env: &Env, r: &I256, z: &I256) -> I256 {
+    // a small value, which does not allocate a host object
     let zero = I256::from_i32(env, 0);
-    let r = x.mul(&y);
-    if r < zero || (r > zero && z.clone() < zero) {
-        // ceiling is taken by default for a negative result
-        let remainder = r.rem_euclid(&z);
-        let one = I256::from_i32(env, 1);
-        r.div(&z).sub(if remainder > zero { &one } else { &zero })
+    let quotient = r.div(z);
+    // ceiling is taken by default for a negative result, so only an inexact negative result is
+    // adjusted. The remainder costs less than multiplying the quotient back.
+    if is_negative(&zero, r, z, &quotient) && r.rem_euclid(z) != zero {
+        quotient.sub(&I256::from_i32(env, 1))
     } else {
-        // floor taken by default for a positive or zero result
-        r.div(&z)
+        quotient
     }
 }
 
 /// Performs ceil(x * y / z)
 pub(crate) fn mul_div_ceil(env: &Env, x: &I256, y: &I256, z: &I256) -> I256 {
+    div_ceil(env, &x.mul(y), z)
+}
+
+/// Performs ceil(r / z)
+pub(crate) fn div_ceil(env: &Env, r: &I256, z: &I256) -> I256 {
+    // a small value, which does not allocate a host object
     let zero = I256::from_i32(env, 0);
-    let r = x.mul(&y);
-    if r <= zero || (r > zero && z.clone() < zero) {
-        // ceiling is taken by default for a negative or zero result
-        r.div(&z)
+    let quotient = r.div(z);
+    // floor is taken by default for a positive result, so only an inexact positive result is
+    // adjusted. The remainder costs less than multiplying the quotient back.
+    if !is_negative(&zero, r, z, &quotient) && r.rem_euclid(z) != zero {
+        quotient.add(&I256::from_i32(env, 1))
     } else {
-        // floor taken by default for a positive result
-        let remainder = r.rem_euclid(&z);
-        let one = I256::from_i32(env, 1);
-        r.div(&z).add(if remainder > zero { &one } else { &zero })
+        quotient
+    }
+}
+
+/// Returns whether r / z is negative, with a single comparison unless the quotient truncated to 0
+fn is_negative(zero: &I256, r: &I256, z: &I256, quotient: &I256) -> bool {
+    match quotient.cmp(zero) {
+        Ordering::Less => true,
+        Ordering::Greater => false,
+        Ordering::Equal => (*r < *zero) != (*z < *zero),
+    }
+}
+
+/// Performs floor(x * y / 2^shift)
+pub(crate) fn mul_shr_floor(x: &I256, y: &I256, shift: u32) -> I256 {
+    // the host performs an arithmetic shift, which takes the floor for a negative result
+    x.mul(y).shr(shift)
+}
+
+/// Performs ceil(x * y / 2^shift)
+pub(crate) fn mul_shr_ceil(env: &Env, x: &I256, y: &I256, shift: u32) -> I256 {
+    let r = x.mul(y);
+    let quotient = r.shr(shift);
+    // any bits dropped by the shift are the remainder
+    if quotient.shl(shift) == r {
+        quotient
+    } else {
+        quotient.add(&I256::from_i32(env, 1))
+    }
+}
+
+/// Performs x * 2^shift
+///
+/// ### Panics
+/// If the result does not fit in an I256
+pub(crate) fn shl_checked(x: &I256, shift: u32) -> I256 {
+    // the host drops any bits shifted out, so verify the shift is reversible
+    let r = x.shl(shift);
+    if r.shr(shift) != *x {
+        panic!("attempt to shift left with overflow");
+    }
+    r
+}
+
+/// Converts x to an I256 from its 64 bit parts, which costs less than `I256::from_i128`
+pub(crate) fn from_i128(env: &Env, x: i128) -> I256 {
+    let sign = if x < 0 { -1 } else { 0 };
+    I256::from_parts(env, sign, sign as u64, (x >> 64) as u64, x as u64)
+}
+
+/// Converts x to an i128 from its big endian bytes, which costs less than `I256::to_i128`, or
+/// None if x does not fit in an i128
+pub(crate) fn to_i128(x: &I256) -> Option<i128> {
+    let mut buf = [0u8; 32];
+    x.to_be_bytes().copy_into_slice(&mut buf);
+    let (hi, lo) = buf.split_at(16);
+    let hi = i128::from_be_bytes(hi.try_into().unwrap_optimized());
+    let lo = i128::from_be_bytes(lo.try_into().unwrap_optimized());
+    // the high half must only extend the sign of the low half
+    if hi == lo >> 127 {
+        Some(lo)
+    } else {
+        None
+    }
+}
+
+/// Performs scale - fee_rate
+///
+/// ### Panics
+/// If fee_rate is not within [0, scale)
+fn fee_complement(env: &Env, fee_rate: &I256, scale: &I256) -> I256 {
+    let zero = I256::from_i32(env, 0);
+    if *fee_rate < zero || fee_rate >= scale {
+        panic!("fee rate not within [0, scale)");
     }
+    scale.sub(fee_rate)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::budget::{baseline_i256_mul_div_ceil, baseline_i256_mul_div_floor, measure};
 
     /********** fixed_mul_floor **********/
 
@@ -80,6 +193,18 @@ mod tests {
         assert_eq!(result, I256::from_i128(&env, -483_5313676));
     }
 
+    #[test]
+    fn test_fixed_mul_floor_both_negative_rounds_down() {
+        let env = Env::default();
+        let x: I256 = I256::from_i128(&env, -1_5391283);
+        let y: I256 = I256::from_i128(&env, -314_1592653);
+        let denominator: I256 = I256::from_i128(&env, 1_0000001);
+
+        let result = x.fixed_mul_floor(&env, &y, &denominator);
+
+        assert_eq!(result, I256::from_i128(&env, 483_5313675));
+    }
+
     #[test]
     fn test_fixed_mul_floor_large_number() {
         let env = Env::default();
@@ -131,6 +256,18 @@ mod tests {
         assert_eq!(result, I256::from_i128(&env, -483_5313675));
     }
 
+    #[test]
+    fn test_fixed_mul_ceil_both_negative_rounds_up() {
+        let env = Env::default();
+        let x: I256 = I256::from_i128(&env, 1_5391283);
+        let y: I256 = I256::from_i128(&env, -314_1592653);
+        let denominator: I256 = I256::from_i128(&env, -1_0000001);
+
+        let result = x.fixed_mul_ceil(&env, &y, &denominator);
+
+        assert_eq!(result, I256::from_i128(&env, 483_5313676));
+    }
+
     #[test]
     fn test_fixed_mul_ceil_large_number() {
         let env = Env::default();
@@ -257,4 +394,221 @@ mod tests {
 
         x.fixed_div_ceil(&env, &y, &denominator);
     }
+
+    /********** gross_up **********/
+
+    #[test]
+    fn test_gross_up_above_i128() {
+        let env = Env::default();
+        let net: I256 = I256::from_i128(&env, i128::MAX);
+        let fee_rate: I256 = I256::from_i128(&env, 30000);
+        let scale: I256 = I256::from_i128(&env, 1_0000000);
+
+        let result = net.gross_up(&env, &fee_rate, &scale);
+
+        // 170653142889136641656657275542511640649
+        assert_eq!(
+            result,
+            I256::from_parts(&env, 0, 0, 9251125413094057981, 17447622529095393353)
+        );
+        assert_eq!(result.net_down(&env, &fee_rate, &scale), net);
+    }
+
+    #[test]
+    fn test_gross_up_negative_below_i128() {
+        let env = Env::default();
+        let net: I256 = I256::from_i128(&env, -i128::MAX);
+        let fee_rate: I256 = I256::from_i128(&env, 30000);
+        let scale: I256 = I256::from_i128(&env, 1_0000000);
+
+        let result = net.gross_up(&env, &fee_rate, &scale);
+
+        // -170653142889136641656657275542511640648, rounded up
+        assert_eq!(
+            result,
+            I256::from_parts(&env, -1, u64::MAX, 9195618660615493634, 999121544614158264)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_gross_up_invalid_fee_rate() {
+        let env = Env::default();
+        let net: I256 = I256::from_i128(&env, 1_0000000);
+        let fee_rate: I256 = I256::from_i128(&env, -1);
+        let scale: I256 = I256::from_i128(&env, 1_0000000);
+
+        net.gross_up(&env, &fee_rate, &scale);
+    }
+
+    /********** net_down **********/
+
+    #[test]
+    fn test_net_down_negative_rounds_down() {
+        let env = Env::default();
+        let gross: I256 = I256::from_i128(&env, -1_5437596);
+        let fee_rate: I256 = I256::from_i128(&env, 30000);
+        let scale: I256 = I256::from_i128(&env, 1_0000000);
+
+        let result = gross.net_down(&env, &fee_rate, &scale);
+
+        // the fee taken, gross - net, is rounded up
+        assert_eq!(result, I256::from_i128(&env, -1_5391284));
+    }
+
+    /********** fixed_mul_shr_floor **********/
+
+    #[test]
+    fn test_fixed_mul_shr_floor_q64() {
+        let env = Env::default();
+        // 1.5 * 1.25 = 1.875
+        let x: I256 = I256::from_i128(&env, 3 << 63);
+        let y: I256 = I256::from_i128(&env, 5 << 62);
+
+        let result = x.fixed_mul_shr_floor(&env, &y, 64);
+
+        assert_eq!(result, I256::from_i128(&env, 15 << 61));
+    }
+
+    #[test]
+    fn test_fixed_mul_shr_floor_negative_rounds_down() {
+        let env = Env::default();
+        let x: I256 = I256::from_i128(&env, -3);
+        let y: I256 = I256::from_i128(&env, 1);
+
+        let result = x.fixed_mul_shr_floor(&env, &y, 1);
+
+        assert_eq!(result, I256::from_i128(&env, -2));
+    }
+
+    /********** fixed_mul_shr_ceil **********/
+
+    #[test]
+    fn test_fixed_mul_shr_ceil_rounds_up() {
+        let env = Env::default();
+        let x: I256 = I256::from_i128(&env, 3);
+        let y: I256 = I256::from_i128(&env, 1);
+
+        let result = x.fixed_mul_shr_ceil(&env, &y, 1);
+
+        assert_eq!(result, I256::from_i128(&env, 2));
+    }
+
+    #[test]
+    fn test_fixed_mul_shr_ceil_negative_rounds_up() {
+        let env = Env::default();
+        let x: I256 = I256::from_i128(&env, -3);
+        let y: I256 = I256::from_i128(&env, 1);
+
+        let result = x.fixed_mul_shr_ceil(&env, &y, 1);
+
+        assert_eq!(result, I256::from_i128(&env, -1));
+    }
+
+    /********** fixed_div_shl_floor **********/
+
+    #[test]
+    fn test_fixed_div_shl_floor_negative_rounds_down() {
+        let env = Env::default();
+        let x: I256 = I256::from_i128(&env, -(1 << 64));
+        let y: I256 = I256::from_i128(&env, 3 << 64);
+
+        let result = x.fixed_div_shl_floor(&env, &y, 64);
+
+        assert_eq!(result, I256::from_i128(&env, -6148914691236517206));
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to shift left with overflow")]
+    fn test_fixed_div_shl_floor_overflow() {
+        let env = Env::default();
+        let x: I256 = I256::from_i128(&env, 1);
+
+        x.fixed_div_shl_floor(&env, &x, 255);
+    }
+
+    /********** fixed_div_shl_ceil **********/
+
+    #[test]
+    fn test_fixed_div_shl_ceil_rounds_up() {
+        let env = Env::default();
+        let x: I256 = I256::from_i128(&env, 1 << 64);
+        let y: I256 = I256::from_i128(&env, 3 << 64);
+
+        let result = x.fixed_div_shl_ceil(&env, &y, 64);
+
+        assert_eq!(result, I256::from_i128(&env, 6148914691236517206));
+    }
+
+    /********** budget **********/
+
+    #[test]
+    fn test_fixed_mul_floor_budget() {
+        let env = Env::default();
+        let x: I256 = I256::from_i128(&env, -3 << 100);
+        let y: I256 = I256::from_i128(&env, 5 << 100);
+        let denominator: I256 = I256::from_i128(&env, 1 << 100);
+        let inexact: I256 = I256::from_i128(&env, (1 << 100) + 1);
+
+        // an exact negative result skips the subtraction
+        let (result, cpu, _) = measure(&env, || x.fixed_mul_floor(&env, &y, &denominator));
+        let (expected, baseline_cpu, _) = measure(&env, || {
+            baseline_i256_mul_div_floor(&env, &x, &y, &denominator)
+        });
+        assert_eq!(result, expected);
+        assert!(cpu < baseline_cpu);
+
+        let (result, cpu, _) = measure(&env, || x.fixed_mul_floor(&env, &y, &inexact));
+        let (expected, baseline_cpu, _) =
+            measure(&env, || baseline_i256_mul_div_floor(&env, &x, &y, &inexact));
+        assert_eq!(result, expected);
+        assert!(cpu <= baseline_cpu);
+    }
+
+    #[test]
+    fn test_fixed_mul_ceil_budget() {
+        let env = Env::default();
+        let x: I256 = I256::from_i128(&env, 3 << 100);
+        let y: I256 = I256::from_i128(&env, 5 << 100);
+        let denominator: I256 = I256::from_i128(&env, 1 << 100);
+        let inexact: I256 = I256::from_i128(&env, (1 << 100) + 1);
+
+        // an exact positive result skips the addition
+        let (result, cpu, _) = measure(&env, || x.fixed_mul_ceil(&env, &y, &denominator));
+        let (expected, baseline_cpu, _) = measure(&env, || {
+            baseline_i256_mul_div_ceil(&env, &x, &y, &denominator)
+        });
+        assert_eq!(result, expected);
+        assert!(cpu < baseline_cpu);
+
+        let (result, cpu, _) = measure(&env, || x.fixed_mul_ceil(&env, &y, &inexact));
+        let (expected, baseline_cpu, _) =
+            measure(&env, || baseline_i256_mul_div_ceil(&env, &x, &y, &inexact));
+        assert_eq!(result, expected);
+        assert!(cpu <= baseline_cpu);
+    }
+
+    /********** conversions **********/
+
+    #[test]
+    fn test_from_i128() {
+        let env = Env::default();
+
+        for x in [0, 1, -1, i128::MIN, i128::MAX, -(1 << 64)] {
+            assert_eq!(from_i128(&env, x), I256::from_i128(&env, x));
+        }
+    }
+
+    #[test]
+    fn test_to_i128() {
+        let env = Env::default();
+
+        for x in [0, 1, -1, i128::MIN, i128::MAX, -(1 << 64)] {
+            assert_eq!(to_i128(&I256::from_i128(&env, x)), Some(x));
+        }
+        let max = I256::from_i128(&env, i128::MAX);
+        let one = I256::from_i32(&env, 1);
+        assert_eq!(to_i128(&max.add(&one)), None);
+        assert_eq!(to_i128(&max.add(&one).mul(&I256::from_i32(&env, -2))), None);
+    }
 }
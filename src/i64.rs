@@ -20,9 +20,9 @@ impl FixedPoint for i64 {
 
 /// Performs floor(x * y / z)
 fn mul_div_floor(x: i64, y: i64, z: i64) -> Option<i64> {
-    return match x.checked_mul(y) {
+    match x.checked_mul(y) {
         Some(r) => {
-            if r < 0 || (r > 0 && z < 0) {
+            if (r < 0 && z > 0) || (r > 0 && z < 0) {
                 // ceiling is taken by default for a negative result
                 let remainder = r.checked_rem_euclid(z)?;
                 (r / z).checked_sub(if remainder > 0 { 1 } else { 0 })
@@ -33,19 +33,19 @@ fn mul_div_floor(x: i64, y: i64, z: i64) -> Option<i64> {
         }
         None => {
             let res_i128 = crate::i128::mul_div_floor(x as i128, y as i128, z as i128)?;
-            if res_i128 > i64::MAX as i128 {
+            if res_i128 > i64::MAX as i128 || res_i128 < i64::MIN as i128 {
                 return None;
             }
             Some(res_i128 as i64)
         }
-    };
+    }
 }
 
 /// Performs ceil(x * y / z)
 fn mul_div_ceil(x: i64, y: i64, z: i64) -> Option<i64> {
-    return match x.checked_mul(y) {
+    match x.checked_mul(y) {
         Some(r) => {
-            if r <= 0 || (r > 0 && z < 0) {
+            if (r <= 0 && z > 0) || (r >= 0 && z < 0) {
                 // ceiling is taken by default for a negative or zero result
                 r.checked_div(z)
             } else {
@@ -56,12 +56,12 @@ fn mul_div_ceil(x: i64, y: i64, z: i64) -> Option<i64> {
         }
         None => {
             let res_i128 = crate::i128::mul_div_ceil(x as i128, y as i128, z as i128)?;
-            if res_i128 > i64::MAX as i128 {
+            if res_i128 > i64::MAX as i128 || res_i128 < i64::MIN as i128 {
                 return None;
             }
             Some(res_i128 as i64)
         }
-    };
+    }
 }
 
 #[cfg(test)]
@@ -114,6 +114,28 @@ mod tests {
         assert_eq!(result, None);
     }
 
+    #[test]
+    fn test_fixed_mul_floor_both_negative_rounds_down() {
+        let x: i64 = -1_5391283;
+        let y: i64 = -314_1592653;
+        let denominator: i64 = 1_0000001;
+
+        let result = x.fixed_mul_floor(y, denominator).unwrap();
+
+        assert_eq!(result, 483_5313675)
+    }
+
+    #[test]
+    fn test_fixed_mul_floor_result_underflow() {
+        let x: i64 = -9_223_372_036_000_000_000;
+        let y: i64 = 2_000_000_000;
+        let denominator: i64 = 1_000_000_000;
+
+        let result = x.fixed_mul_floor(y, denominator);
+
+        assert_eq!(result, None);
+    }
+
     /********** fixed_mul_ceil **********/
 
     #[test]
@@ -160,6 +182,28 @@ mod tests {
         assert_eq!(result, None);
     }
 
+    #[test]
+    fn test_fixed_mul_ceil_both_negative_rounds_up() {
+        let x: i64 = 1_5391283;
+        let y: i64 = -314_1592653;
+        let denominator: i64 = -1_0000001;
+
+        let result = x.fixed_mul_ceil(y, denominator).unwrap();
+
+        assert_eq!(result, 483_5313676)
+    }
+
+    #[test]
+    fn test_fixed_mul_ceil_result_underflow() {
+        let x: i64 = -9_223_372_036_000_000_000;
+        let y: i64 = 2_000_000_000;
+        let denominator: i64 = 1_000_000_000;
+
+        let result = x.fixed_mul_ceil(y, denominator);
+
+        assert_eq!(result, None);
+    }
+
     /********** fixed_div_floor **********/
 
     #[test]
@@ -251,4 +295,79 @@ mod tests {
 
         assert_eq!(result, None);
     }
+
+    /********** gross_up **********/
+
+    #[test]
+    fn test_gross_up_rounds_up() {
+        // 100 tokens with 7 decimals, after a 0.3% fee
+        let net: i64 = 100_0000000;
+
+        let result = net.gross_up(30000, 1_0000000).unwrap();
+
+        assert_eq!(result, 100_3009028)
+    }
+
+    #[test]
+    fn test_gross_up_negative_rounds_up() {
+        let net: i64 = -100_0000000;
+
+        let result = net.gross_up(30000, 1_0000000).unwrap();
+
+        assert_eq!(result, -100_3009027)
+    }
+
+    #[test]
+    fn test_gross_up_phantom_overflow_scales() {
+        let net: i64 = i64::MAX / 2;
+
+        let result = net.gross_up(30000, 1_0000000).unwrap();
+
+        assert_eq!(result, 4_625_562_706_547_028_990)
+    }
+
+    #[test]
+    fn test_gross_up_overflow() {
+        let result = i64::MAX.gross_up(1, 1_0000000);
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn test_gross_up_invalid_fee_rate() {
+        let scale: i64 = 1_0000000;
+
+        assert_eq!(100_0000000i64.gross_up(scale, scale), None);
+        assert_eq!(100_0000000i64.gross_up(-1, scale), None);
+    }
+
+    /********** net_down **********/
+
+    #[test]
+    fn test_net_down_rounds_down() {
+        let gross: i64 = 100_3009028;
+
+        let result = gross.net_down(30000, 1_0000000).unwrap();
+
+        assert_eq!(result, 100_0000000)
+    }
+
+    #[test]
+    fn test_net_down_negative_rounds_down() {
+        let gross: i64 = -100_3009028;
+
+        let result = gross.net_down(30000, 1_0000000).unwrap();
+
+        // the fee taken, gross - net, is rounded up
+        assert_eq!(result, -100_0000001)
+    }
+
+    #[test]
+    fn test_net_down_negative_is_inverse_of_gross_up() {
+        let net: i64 = -100_0000000;
+
+        let gross = net.gross_up(30000, 1_0000000).unwrap();
+
+        assert_eq!(gross.net_down(30000, 1_0000000).unwrap(), net);
+    }
 }
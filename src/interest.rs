@@ -0,0 +1,313 @@
+use soroban_sdk::{unwrap::UnwrapOptimized, Env, I256};
+
+use crate::SorobanFixedPoint;
+
+/// The number of seconds in a 365 day year
+pub const SECONDS_PER_YEAR: u64 = 31_536_000;
+
+// @dev - the growth factor (1 + r)^t is approximated with the first four terms of its binomial expansion:
+//
+//   (1 + r)^t ~= 1 + t*r + t*(t-1)/2 * r^2 + t*(t-1)*(t-2)/6 * r^3
+//
+// Each term is derived from the previous one, term_k = term_(k-1) * r * (t-k+1) / k, so rounding only
+// introduces an error of 1 unit per term regardless of the size of t.
+//
+// For r >= 0 every omitted term is positive, so the approximation never overstates the true growth. The
+// relative error is bounded by (r*t)^4 / 24 * e^(r*t). For example, 10% of total growth over the accrual
+// period (r*t = 0.1) underestimates by at most ~4.6e-6, and 1% by at most ~4.2e-10.
+//
+/// Compound interest trait for accruing interest over a number of periods.
+///
+/// Rates are expected to be non-negative and scaled by `scale`. For example, with a scale of 1_0000000,
+/// a rate of 0.5% per period is represented as 50000.
+///
+/// Interest is accrued with the `SorobanFixedPoint` methods, so all arithmetic errors panic.
+pub trait CompoundInterest: Sized {
+    /// Calculates x * (1 + rate_per_second)^seconds, rounding each step down.
+    ///
+    /// ### Panics
+    /// This method will panic if an overflow occurs or the result does not fit in Self.
+    fn compound(&self, env: &Env, rate_per_second: &Self, seconds: u64, scale: &Self) -> Self;
+
+    /// Calculates the next value of the index x after accruing interest for `periods` at
+    /// `rate_per_period`, or x * (1 + rate_per_period)^periods, rounding each step up.
+    ///
+    /// Rounding up ensures a debt index never under-accrues interest. Use `compound` to
+    /// accrue a value where rounding down favors the protocol.
+    ///
+    /// ### Panics
+    /// This method will panic if an overflow occurs or the result does not fit in Self.
+    fn accrue_index(&self, env: &Env, rate_per_period: &Self, periods: u64, scale: &Self) -> Self;
+
+    /// Converts x as an APR into an APY, compounded `periods_per_year` times, rounding down.
+    ///
+    /// ### Panics
+    /// This method will panic if `periods_per_year` is 0, an overflow occurs, or the result
+    /// does not fit in Self.
+    fn apr_to_apy(&self, env: &Env, periods_per_year: u64, scale: &Self) -> Self;
+}
+
+impl CompoundInterest for i128 {
+    fn compound(&self, env: &Env, rate_per_second: &i128, seconds: u64, scale: &i128) -> i128 {
+        let factor = growth_factor(env, rate_per_second, seconds, scale, i128::fixed_mul_floor);
+        self.fixed_mul_floor(env, &factor, scale)
+    }
+
+    fn accrue_index(&self, env: &Env, rate_per_period: &i128, periods: u64, scale: &i128) -> i128 {
+        let factor = growth_factor(env, rate_per_period, periods, scale, i128::fixed_mul_ceil);
+        self.fixed_mul_ceil(env, &factor, scale)
+    }
+
+    fn apr_to_apy(&self, env: &Env, periods_per_year: u64, scale: &i128) -> i128 {
+        let rate_per_period = self / periods_per_year as i128;
+        let factor = growth_factor(
+            env,
+            &rate_per_period,
+            periods_per_year,
+            scale,
+            i128::fixed_mul_floor,
+        );
+        factor.checked_sub(*scale).unwrap_optimized()
+    }
+}
+
+/// Performs (1 + r)^t, using `mul` to round each term
+fn growth_factor(
+    env: &Env,
+    r: &i128,
+    t: u64,
+    scale: &i128,
+    mul: fn(&i128, &Env, &i128, &i128) -> i128,
+) -> i128 {
+    if t == 0 {
+        return *scale;
+    }
+    let t = t as i128;
+    let first = r.checked_mul(t).unwrap_optimized();
+    let second = mul(
+        &first,
+        env,
+        &r.checked_mul(t - 1).unwrap_optimized(),
+        &scale.checked_mul(2).unwrap_optimized(),
+    );
+    let third = mul(
+        &second,
+        env,
+        &r.checked_mul(t - 2).unwrap_optimized(),
+        &scale.checked_mul(3).unwrap_optimized(),
+    );
+    scale
+        .checked_add(first)
+        .and_then(|f| f.checked_add(second))
+        .and_then(|f| f.checked_add(third))
+        .unwrap_optimized()
+}
+
+impl CompoundInterest for I256 {
+    fn compound(&self, env: &Env, rate_per_second: &I256, seconds: u64, scale: &I256) -> I256 {
+        let factor =
+            growth_factor_i256(env, rate_per_second, seconds, scale, I256::fixed_mul_floor);
+        self.fixed_mul_floor(env, &factor, scale)
+    }
+
+    fn accrue_index(&self, env: &Env, rate_per_period: &I256, periods: u64, scale: &I256) -> I256 {
+        let factor = growth_factor_i256(env, rate_per_period, periods, scale, I256::fixed_mul_ceil);
+        self.fixed_mul_ceil(env, &factor, scale)
+    }
+
+    fn apr_to_apy(&self, env: &Env, periods_per_year: u64, scale: &I256) -> I256 {
+        let rate_per_period = self.div(&I256::from_i128(env, periods_per_year as i128));
+        let factor = growth_factor_i256(
+            env,
+            &rate_per_period,
+            periods_per_year,
+            scale,
+            I256::fixed_mul_floor,
+        );
+        factor.sub(scale)
+    }
+}
+
+/// Performs (1 + r)^t, using `mul` to round each term
+fn growth_factor_i256(
+    env: &Env,
+    r: &I256,
+    t: u64,
+    scale: &I256,
+    mul: fn(&I256, &Env, &I256, &I256) -> I256,
+) -> I256 {
+    if t == 0 {
+        return scale.clone();
+    }
+    let one = I256::from_i32(env, 1);
+    let t = I256::from_i128(env, t as i128);
+    let t_1 = t.sub(&one);
+    let t_2 = t_1.sub(&one);
+    let first = r.mul(&t);
+    let second = mul(
+        &first,
+        env,
+        &r.mul(&t_1),
+        &scale.mul(&I256::from_i32(env, 2)),
+    );
+    let third = mul(
+        &second,
+        env,
+        &r.mul(&t_2),
+        &scale.mul(&I256::from_i32(env, 3)),
+    );
+    scale.add(&first).add(&second).add(&third)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /********** compound **********/
+
+    #[test]
+    fn test_compound_zero_seconds() {
+        let env = Env::default();
+        let principal: i128 = 100_0000000;
+        let rate: i128 = 1_000_000_000;
+        let scale: i128 = 10i128.pow(18);
+
+        let result = principal.compound(&env, &rate, 0, &scale);
+
+        assert_eq!(result, principal);
+    }
+
+    #[test]
+    fn test_compound_one_second_is_simple_interest() {
+        let env = Env::default();
+        let principal: i128 = 100_0000000;
+        let rate: i128 = 1_0000; // 0.1% per period
+        let scale: i128 = 1_0000000;
+
+        let result = principal.compound(&env, &rate, 1, &scale);
+
+        assert_eq!(result, 100_1000000);
+    }
+
+    #[test]
+    fn test_compound_per_second() {
+        let env = Env::default();
+        let principal: i128 = 1000000_0000000;
+        // ~10% APR per second at 18 decimals
+        let rate: i128 = 3_170_979_198;
+        let scale: i128 = 10i128.pow(18);
+
+        let result = principal.compound(&env, &rate, SECONDS_PER_YEAR, &scale);
+
+        // (1 + r)^t ~= e^0.1 = 1.105170918..., within the error bound of the approximation
+        assert_eq!(result, 1105166_6664791);
+    }
+
+    #[test]
+    fn test_compound_per_period() {
+        let env = Env::default();
+        let principal: i128 = 1000_0000000;
+        let rate: i128 = 1_0000; // 0.1% per period
+        let scale: i128 = 1_0000000;
+
+        let result = principal.compound(&env, &rate, 10, &scale);
+
+        // 1.001^10 = 1.0100451201...
+        assert_eq!(result, 1010_0451000);
+    }
+
+    #[test]
+    fn test_compound_i256_matches_i128() {
+        let env = Env::default();
+        let principal: i128 = 1000000_0000000;
+        let rate: i128 = 3_170_979_198;
+        let scale: i128 = 10i128.pow(18);
+
+        let result = I256::from_i128(&env, principal).compound(
+            &env,
+            &I256::from_i128(&env, rate),
+            SECONDS_PER_YEAR,
+            &I256::from_i128(&env, scale),
+        );
+
+        assert_eq!(
+            result,
+            I256::from_i128(
+                &env,
+                principal.compound(&env, &rate, SECONDS_PER_YEAR, &scale)
+            )
+        );
+    }
+
+    /********** accrue_index **********/
+
+    #[test]
+    fn test_accrue_index_rounds_up() {
+        let env = Env::default();
+        let index: i128 = 1_0000000;
+        let rate: i128 = 1_0000;
+        let scale: i128 = 1_0000000;
+
+        let floor = index.compound(&env, &rate, 10, &scale);
+        let ceil = index.accrue_index(&env, &rate, 10, &scale);
+
+        assert_eq!(floor, 1_0100451);
+        assert_eq!(ceil, 1_0100452);
+    }
+
+    #[test]
+    fn test_accrue_index_i256() {
+        let env = Env::default();
+        let index = I256::from_i128(&env, 1_0000000);
+        let rate = I256::from_i128(&env, 1_0000);
+        let scale = I256::from_i128(&env, 1_0000000);
+
+        let result = index.accrue_index(&env, &rate, 10, &scale);
+
+        assert_eq!(result, I256::from_i128(&env, 1_0100452));
+    }
+
+    /********** apr_to_apy **********/
+
+    #[test]
+    fn test_apr_to_apy() {
+        let env = Env::default();
+        let apr: i128 = 10i128.pow(17); // 10%
+        let scale: i128 = 10i128.pow(18);
+
+        let result = apr.apr_to_apy(&env, SECONDS_PER_YEAR, &scale);
+
+        // e^0.1 - 1 = 0.105170918..., within the error bound of the approximation
+        assert_eq!(result, 105_166_666_479_144_251);
+    }
+
+    #[test]
+    fn test_apr_to_apy_i256() {
+        let env = Env::default();
+        let apr = I256::from_i128(&env, 1000000); // 10%
+        let scale = I256::from_i128(&env, 1_0000000);
+
+        let result = apr.apr_to_apy(&env, 12, &scale);
+
+        // (1 + 0.1 / 12)^12 - 1 = 0.1047130...
+        assert_eq!(result, I256::from_i128(&env, 1047101));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_compound_scale_overflow() {
+        let env = Env::default();
+        let scale: i128 = i128::MAX / 2 + 1;
+
+        1i128.compound(&env, &1, 2, &scale);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_apr_to_apy_zero_periods() {
+        let env = Env::default();
+        let apr: i128 = 1000000;
+
+        apr.apr_to_apy(&env, 0, &1_0000000);
+    }
+}
@@ -0,0 +1,283 @@
+use crate::{scale::SCALE_7, FixedPoint};
+
+/// The minimum value of the rate modifier (0.1)
+pub const MIN_RATE_MODIFIER: i128 = 1000000;
+
+/// The maximum value of the rate modifier (10)
+pub const MAX_RATE_MODIFIER: i128 = 10_0000000;
+
+/// A kinked utilization interest rate model.
+///
+/// All values are fixed point numbers with 7 decimals. Below the optimal utilization, the borrow rate
+/// grows linearly from `base_rate` to `base_rate + slope_1`. Above it, the borrow rate grows linearly
+/// from `base_rate + slope_1` to `base_rate + slope_1 + slope_2` at 100% utilization.
+///
+/// If a `reactivity` is set, the borrow rate is additionally scaled by a rate modifier that drifts
+/// towards higher rates while utilization is above optimal, and lower rates while below it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IrModel {
+    pub base_rate: i128,
+    pub slope_1: i128,
+    pub slope_2: i128,
+    /// The optimal utilization. Must be within (0, 1).
+    pub optimal_util: i128,
+    /// The rate the rate modifier changes per second, per unit of utilization away from optimal
+    pub reactivity: Option<i128>,
+}
+
+impl IrModel {
+    /// Calculates the borrow rate at utilization `util`, rounding up.
+    ///
+    /// Returns None if an overflow occurs or the optimal utilization is not within (0, 1).
+    pub fn borrow_rate(&self, util: i128) -> Option<i128> {
+        let optimal_util = self.checked_optimal_util()?;
+        if util <= optimal_util {
+            let util_scalar = util.fixed_div_ceil(optimal_util, SCALE_7)?;
+            let slope_rate = util_scalar.fixed_mul_ceil(self.slope_1, SCALE_7)?;
+            self.base_rate.checked_add(slope_rate)
+        } else {
+            let excess_util = util.checked_sub(optimal_util)?;
+            let util_scalar = excess_util.fixed_div_ceil(SCALE_7 - optimal_util, SCALE_7)?;
+            let slope_rate = util_scalar.fixed_mul_ceil(self.slope_2, SCALE_7)?;
+            self.base_rate
+                .checked_add(self.slope_1)?
+                .checked_add(slope_rate)
+        }
+    }
+
+    /// Calculates the borrow rate at utilization `util` scaled by `rate_modifier`, rounding up.
+    ///
+    /// Returns None if an overflow occurs or the optimal utilization is not within (0, 1).
+    pub fn borrow_rate_with_modifier(&self, util: i128, rate_modifier: i128) -> Option<i128> {
+        self.borrow_rate(util)?
+            .fixed_mul_ceil(rate_modifier, SCALE_7)
+    }
+
+    /// Calculates the supply rate from the `borrow_rate` at utilization `util`, after `reserve_factor`
+    /// of the interest is taken by the protocol, rounding down.
+    ///
+    /// Returns None if an overflow occurs or the reserve factor is not within [0, 1).
+    pub fn supply_rate(&self, borrow_rate: i128, util: i128, reserve_factor: i128) -> Option<i128> {
+        borrow_rate
+            .fixed_mul_floor(util, SCALE_7)?
+            .net_down(reserve_factor, SCALE_7)
+    }
+
+    /// Calculates the next rate modifier after `elapsed` seconds at utilization `util`. The result
+    /// is clamped between `MIN_RATE_MODIFIER` and `MAX_RATE_MODIFIER`.
+    ///
+    /// Returns `rate_modifier` unchanged if the model has no reactivity, or None if an overflow occurs
+    /// or the optimal utilization is not within (0, 1).
+    pub fn next_rate_modifier(
+        &self,
+        rate_modifier: i128,
+        util: i128,
+        elapsed: u64,
+    ) -> Option<i128> {
+        let reactivity = match self.reactivity {
+            Some(reactivity) => reactivity,
+            None => return Some(rate_modifier),
+        };
+        let util_dif_scaled = util
+            .checked_sub(self.checked_optimal_util()?)?
+            .checked_mul(elapsed as i128)?;
+        let next_rate_modifier = if util_dif_scaled > 0 {
+            // rate modifier increasing, round up
+            let change = util_dif_scaled.fixed_mul_ceil(reactivity, SCALE_7)?;
+            rate_modifier.checked_add(change)?
+        } else {
+            // rate modifier decreasing, round down
+            let change = util_dif_scaled.fixed_mul_floor(reactivity, SCALE_7)?;
+            rate_modifier.checked_add(change)?
+        };
+        Some(next_rate_modifier.clamp(MIN_RATE_MODIFIER, MAX_RATE_MODIFIER))
+    }
+
+    /// Returns the optimal utilization, or None if it is not within (0, 1)
+    fn checked_optimal_util(&self) -> Option<i128> {
+        if self.optimal_util <= 0 || self.optimal_util >= SCALE_7 {
+            return None;
+        }
+        Some(self.optimal_util)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model() -> IrModel {
+        IrModel {
+            base_rate: 100000,
+            slope_1: 400000,
+            slope_2: 1_0000000,
+            optimal_util: 8000000,
+            reactivity: None,
+        }
+    }
+
+    /********** borrow_rate **********/
+
+    #[test]
+    fn test_borrow_rate_zero_util() {
+        let result = model().borrow_rate(0).unwrap();
+
+        assert_eq!(result, 100000);
+    }
+
+    #[test]
+    fn test_borrow_rate_below_optimal_rounds_up() {
+        let result = model().borrow_rate(3333333).unwrap();
+
+        // 0.01 + 0.3333333 / 0.8 * 0.04
+        assert_eq!(result, 266667);
+    }
+
+    #[test]
+    fn test_borrow_rate_at_optimal() {
+        let result = model().borrow_rate(8000000).unwrap();
+
+        assert_eq!(result, 500000);
+    }
+
+    #[test]
+    fn test_borrow_rate_above_optimal() {
+        let result = model().borrow_rate(9000000).unwrap();
+
+        // 0.01 + 0.04 + 0.1 / 0.2 * 1
+        assert_eq!(result, 5500000);
+    }
+
+    #[test]
+    fn test_borrow_rate_full_util() {
+        let result = model().borrow_rate(1_0000000).unwrap();
+
+        assert_eq!(result, 1_0500000);
+    }
+
+    #[test]
+    fn test_borrow_rate_with_modifier() {
+        let result = model()
+            .borrow_rate_with_modifier(8000000, 1_5000000)
+            .unwrap();
+
+        assert_eq!(result, 750000);
+    }
+
+    #[test]
+    fn test_borrow_rate_invalid_optimal_util() {
+        for optimal_util in [-1, 0, 1_0000000] {
+            let model = IrModel {
+                optimal_util,
+                ..model()
+            };
+
+            assert_eq!(model.borrow_rate(8000000), None);
+        }
+    }
+
+    #[test]
+    fn test_borrow_rate_overflow() {
+        let result = model().borrow_rate(i128::MAX);
+
+        assert_eq!(result, None);
+    }
+
+    /********** supply_rate **********/
+
+    #[test]
+    fn test_supply_rate_rounds_down() {
+        let model = model();
+        let util = 3333333;
+        let borrow_rate = model.borrow_rate(util).unwrap();
+
+        let result = model.supply_rate(borrow_rate, util, 1000000).unwrap();
+
+        // 0.0266667 * 0.3333333 * 0.9 = 0.00799999...
+        assert_eq!(result, 79999);
+    }
+
+    #[test]
+    fn test_supply_rate_invalid_reserve_factor() {
+        let result = model().supply_rate(500000, 8000000, 1_0000000);
+
+        assert_eq!(result, None);
+    }
+
+    /********** next_rate_modifier **********/
+
+    #[test]
+    fn test_next_rate_modifier_no_reactivity() {
+        let result = model()
+            .next_rate_modifier(1_0000000, 9500000, 1000)
+            .unwrap();
+
+        assert_eq!(result, 1_0000000);
+    }
+
+    #[test]
+    fn test_next_rate_modifier_above_optimal_increases() {
+        let model = IrModel {
+            reactivity: Some(200),
+            ..model()
+        };
+
+        let result = model.next_rate_modifier(1_0000000, 9000000, 1000).unwrap();
+
+        // 1 + 0.1 * 1000 * 0.00002
+        assert_eq!(result, 1_0020000);
+    }
+
+    #[test]
+    fn test_next_rate_modifier_below_optimal_decreases() {
+        let model = IrModel {
+            reactivity: Some(200),
+            ..model()
+        };
+
+        let result = model.next_rate_modifier(1_0000000, 7000000, 1000).unwrap();
+
+        assert_eq!(result, 9980000);
+    }
+
+    #[test]
+    fn test_next_rate_modifier_clamps() {
+        let model = IrModel {
+            reactivity: Some(200),
+            ..model()
+        };
+
+        let high = model
+            .next_rate_modifier(9_9990000, 1_0000000, 100000)
+            .unwrap();
+        let low = model.next_rate_modifier(1010000, 0, 100000).unwrap();
+
+        assert_eq!(high, MAX_RATE_MODIFIER);
+        assert_eq!(low, MIN_RATE_MODIFIER);
+    }
+
+    #[test]
+    fn test_next_rate_modifier_overflow() {
+        let model = IrModel {
+            reactivity: Some(200),
+            ..model()
+        };
+
+        let result = model.next_rate_modifier(1_0000000, i128::MIN, 1);
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn test_next_rate_modifier_invalid_optimal_util() {
+        let model = IrModel {
+            optimal_util: -1,
+            reactivity: Some(200),
+            ..model()
+        };
+
+        let result = model.next_rate_modifier(1_0000000, 9000000, 1000);
+
+        assert_eq!(result, None);
+    }
+}
@@ -2,14 +2,35 @@
 
 pub const STROOP: u64 = 1_0000000;
 
+pub mod amm;
+pub mod auction;
+pub mod bonding_curve;
+#[cfg(test)]
+mod budget;
+pub mod decimal;
+pub mod display;
+#[cfg(feature = "float")]
+pub mod float;
 pub mod i128;
 pub mod i256;
 pub mod i64;
+pub mod interest;
+pub mod ir_model;
+pub mod log_exp;
+pub mod root;
+pub mod scale;
+pub mod stats;
+pub mod stroop;
+pub mod trig;
 pub mod u128;
 pub mod u256;
 pub mod u64;
+pub mod vault;
+pub mod wad_ray;
 
 mod fixed_point;
 pub use fixed_point::FixedPoint;
+mod rounding;
+pub use rounding::Rounding;
 mod soroban_fixed_point;
-pub use soroban_fixed_point::SorobanFixedPoint;
+pub use soroban_fixed_point::{SorobanFixedPoint, SorobanFixedPointExt};
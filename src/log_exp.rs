@@ -0,0 +1,299 @@
+use crate::{scale::SCALE_18, FixedPoint};
+
+/// ln(2) as a fixed point number with 18 decimals
+pub const LN_2: i128 = 693_147_180_559_945_309;
+
+/// The largest x where exp(x) can be computed. The result, e^46, is ~9.5e19.
+pub const MAX_NATURAL_EXPONENT: i128 = 46 * SCALE_18;
+
+/// The smallest x where exp(x) does not round to 0.
+pub const MIN_NATURAL_EXPONENT: i128 = -42 * SCALE_18;
+
+/// The maximum relative error of `pow`, 1e-14, as a fixed point number with 18 decimals.
+pub const MAX_POW_RELATIVE_ERROR: i128 = 10_000;
+
+// @dev - all functions operate on fixed point numbers with 18 decimals.
+//
+// log2 is computed with the iterative squaring method, producing one bit of the fractional part per
+// iteration. More detail can be found here: https://en.wikipedia.org/wiki/Binary_logarithm#Iterative_approximation
+//
+// exp is computed by reducing x to r = x - k*ln(2) with |r| <= ln(2)/2, evaluating the Taylor series of e^r,
+// and then scaling the result by 2^k.
+//
+// Precision:
+// * log2 and ln have an absolute error of at most ~1e-16
+// * exp has a relative error of at most ~1e-16, plus 1 unit for results below 1
+// * pow has a relative error of at most `MAX_POW_RELATIVE_ERROR`, plus 1 unit
+
+/// Calculates log2(x).
+///
+/// Returns None if x is not positive.
+pub fn log2(x: i128) -> Option<i128> {
+    if x <= 0 {
+        return None;
+    }
+    if x < SCALE_18 {
+        // log2(x) = -log2(1/x)
+        let inverse = (SCALE_18 * SCALE_18) / x;
+        return Some(-log2_gte_one(inverse));
+    }
+    Some(log2_gte_one(x))
+}
+
+/// Performs log2(x) for x >= 1
+fn log2_gte_one(x: i128) -> i128 {
+    // integer part of the result
+    let n = (x / SCALE_18).ilog2();
+    let mut result = n as i128 * SCALE_18;
+
+    // y is within [1, 2)
+    let mut y = x >> n;
+    if y == SCALE_18 {
+        return result;
+    }
+
+    // fractional part of the result
+    let mut delta = SCALE_18 / 2;
+    while delta > 0 {
+        y = y * y / SCALE_18;
+        if y >= 2 * SCALE_18 {
+            result += delta;
+            y >>= 1;
+        }
+        delta >>= 1;
+    }
+    result
+}
+
+/// Calculates ln(x).
+///
+/// Returns None if x is not positive.
+pub fn ln(x: i128) -> Option<i128> {
+    log2(x)?.fixed_mul_floor(LN_2, SCALE_18)
+}
+
+/// Calculates e^x.
+///
+/// Returns 0 if x is less than `MIN_NATURAL_EXPONENT`, or None if x is greater
+/// than `MAX_NATURAL_EXPONENT`.
+pub fn exp(x: i128) -> Option<i128> {
+    if x > MAX_NATURAL_EXPONENT {
+        return None;
+    }
+    if x < MIN_NATURAL_EXPONENT {
+        return Some(0);
+    }
+
+    // x = k*ln(2) + r, with k rounded to the nearest integer
+    let half_ln_2 = if x >= 0 { LN_2 / 2 } else { -LN_2 / 2 };
+    let k = (x + half_ln_2) / LN_2;
+    let r = x - k * LN_2;
+
+    // e^r = 1 + r + r^2/2! + r^3/3! + ...
+    let mut sum = SCALE_18;
+    let mut term = SCALE_18;
+    let mut n: i128 = 1;
+    loop {
+        term = term * r / (n * SCALE_18);
+        if term == 0 {
+            break;
+        }
+        sum += term;
+        n += 1;
+    }
+
+    // e^x = e^r * 2^k
+    if k >= 0 {
+        sum.checked_mul(1 << k)
+    } else {
+        Some(sum >> -k)
+    }
+}
+
+/// Calculates x^y.
+///
+/// Returns None if x is negative, or if the result is too large to compute.
+pub fn pow(x: i128, y: i128) -> Option<i128> {
+    if y == 0 {
+        return Some(SCALE_18);
+    }
+    if x == 0 {
+        return if y > 0 { Some(0) } else { None };
+    }
+    let exponent = ln(x)?.fixed_mul_floor(y, SCALE_18)?;
+    exp(exponent)
+}
+
+/// Calculates x^y, rounded up such that the result is never less than the exact value.
+///
+/// Returns None if x is negative, or if the result is too large to compute.
+pub fn pow_up(x: i128, y: i128) -> Option<i128> {
+    if y == SCALE_18 {
+        return Some(x);
+    }
+    if y == 2 * SCALE_18 {
+        return x.fixed_mul_ceil(x, SCALE_18);
+    }
+    let result = pow(x, y)?;
+    let max_error = result.fixed_mul_ceil(MAX_POW_RELATIVE_ERROR, SCALE_18)? + 1;
+    result.checked_add(max_error)
+}
+
+/// Calculates x^y, rounded down such that the result is never more than the exact value.
+///
+/// Returns None if x is negative, or if the result is too large to compute.
+pub fn pow_down(x: i128, y: i128) -> Option<i128> {
+    if y == SCALE_18 {
+        return Some(x);
+    }
+    if y == 2 * SCALE_18 {
+        return x.fixed_mul_floor(x, SCALE_18);
+    }
+    let result = pow(x, y)?;
+    let max_error = result.fixed_mul_ceil(MAX_POW_RELATIVE_ERROR, SCALE_18)? + 1;
+    Some((result - max_error).max(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Asserts |result - expected| <= tolerance
+    fn assert_approx_eq(result: i128, expected: i128, tolerance: i128) {
+        assert!(
+            (result - expected).abs() <= tolerance,
+            "result: {}, expected: {}",
+            result,
+            expected
+        );
+    }
+
+    /********** log2 **********/
+
+    #[test]
+    fn test_log2_powers_of_two() {
+        assert_eq!(log2(SCALE_18).unwrap(), 0);
+        assert_eq!(log2(8 * SCALE_18).unwrap(), 3 * SCALE_18);
+        assert_eq!(log2(SCALE_18 / 4).unwrap(), -2 * SCALE_18);
+    }
+
+    #[test]
+    fn test_log2() {
+        let result = log2(3 * SCALE_18).unwrap();
+
+        // log2(3) = 1.584962500721156181...
+        assert_approx_eq(result, 1_584_962_500_721_156_181, 100);
+    }
+
+    #[test]
+    fn test_log2_not_positive() {
+        assert_eq!(log2(0), None);
+        assert_eq!(log2(-1), None);
+    }
+
+    /********** ln **********/
+
+    #[test]
+    fn test_ln() {
+        let result = ln(10 * SCALE_18).unwrap();
+
+        // ln(10) = 2.302585092994045684...
+        assert_approx_eq(result, 2_302_585_092_994_045_684, 100);
+    }
+
+    #[test]
+    fn test_ln_less_than_one() {
+        let result = ln(SCALE_18 / 3).unwrap();
+
+        // ln(1/3) = -1.098612288668109691...
+        assert_approx_eq(result, -1_098_612_288_668_109_691, 100);
+    }
+
+    #[test]
+    fn test_ln_smallest_value() {
+        let result = ln(1).unwrap();
+
+        // ln(1e-18) = -41.446531673892822312...
+        assert_approx_eq(result, -41_446_531_673_892_822_312, 100);
+    }
+
+    /********** exp **********/
+
+    #[test]
+    fn test_exp() {
+        assert_eq!(exp(0).unwrap(), SCALE_18);
+
+        // e = 2.718281828459045235...
+        assert_approx_eq(exp(SCALE_18).unwrap(), 2_718_281_828_459_045_235, 100);
+        // e^-1 = 0.367879441171442321...
+        assert_approx_eq(exp(-SCALE_18).unwrap(), 367_879_441_171_442_321, 100);
+    }
+
+    #[test]
+    fn test_exp_max() {
+        let result = exp(MAX_NATURAL_EXPONENT).unwrap();
+
+        // e^46 = 94961194206024488745.13...
+        let expected: i128 = 94_961_194_206_024_488_745_133_649_117_118_323_102;
+        assert_approx_eq(result, expected, expected / 10i128.pow(16));
+    }
+
+    #[test]
+    fn test_exp_out_of_bounds() {
+        assert_eq!(exp(MAX_NATURAL_EXPONENT + 1), None);
+        assert_eq!(exp(MIN_NATURAL_EXPONENT - 1).unwrap(), 0);
+    }
+
+    /********** pow **********/
+
+    #[test]
+    fn test_pow() {
+        let result = pow(2 * SCALE_18, 500_000_000_000_000_000).unwrap();
+
+        // sqrt(2) = 1.414213562373095048...
+        assert_approx_eq(result, 1_414_213_562_373_095_048, 100);
+    }
+
+    #[test]
+    fn test_pow_zero() {
+        assert_eq!(pow(0, SCALE_18).unwrap(), 0);
+        assert_eq!(pow(0, 0).unwrap(), SCALE_18);
+        assert_eq!(pow(5 * SCALE_18, 0).unwrap(), SCALE_18);
+        assert_eq!(pow(0, -SCALE_18), None);
+    }
+
+    #[test]
+    fn test_pow_negative_base() {
+        assert_eq!(pow(-SCALE_18, SCALE_18), None);
+    }
+
+    /********** pow_up and pow_down **********/
+
+    #[test]
+    fn test_pow_up_and_down_bound_exact_value() {
+        // 0.9^3.5 = 0.691590124278824559...
+        let exact: i128 = 691_590_124_278_824_559;
+        let x: i128 = 900_000_000_000_000_000;
+        let y: i128 = 3_500_000_000_000_000_000;
+
+        let up = pow_up(x, y).unwrap();
+        let down = pow_down(x, y).unwrap();
+
+        assert!(up >= exact);
+        assert!(down <= exact);
+        assert!(up - down <= 2 * exact / 10i128.pow(14) + 4);
+    }
+
+    #[test]
+    fn test_pow_up_and_down_exact_exponents() {
+        let x: i128 = 1_500_000_000_000_000_001;
+
+        assert_eq!(pow_up(x, SCALE_18).unwrap(), x);
+        assert_eq!(pow_down(x, SCALE_18).unwrap(), x);
+        assert_eq!(pow_up(x, 2 * SCALE_18).unwrap(), 2_250_000_000_000_000_004);
+        assert_eq!(
+            pow_down(x, 2 * SCALE_18).unwrap(),
+            2_250_000_000_000_000_003
+        );
+    }
+}
@@ -0,0 +1,454 @@
+use soroban_sdk::{unwrap::UnwrapOptimized, Env, U256};
+
+use crate::{u256, Rounding};
+
+// @dev - the nth root of a fixed point number x with scale s is root_n(x / s) * s, or
+// root_n(x * s^(n-1)), so the root is taken of an integer and no precision is lost before rounding.
+//
+// The integer root is found with Newton's method, z' = ((n-1) * z + r / z^(n-1)) / n, starting from
+// a power of 2 at least as large as the root. By the AM-GM inequality, each iteration with floor
+// division stays at or above floor(root) while decreasing, so the first iteration that does not
+// decrease returns the floor of the root.
+//
+// r / z^(n-1) is computed with repeated division, floor(floor(r / z) / z) = floor(r / z^2), so no
+// intermediate value can overflow. The next estimate only decreases if r / z^(n-1) < z, which is
+// checked first so (n-1) * z + r / z^(n-1) cannot overflow either.
+//
+// Precision:
+// * the result is exact, rounded according to the `Rounding` argument
+
+/// Fixed point roots trait for computing the nth root of a fixed point number.
+///
+/// The `u128` and `i128` implementations fall back to 256 bit math if x * scale^(n-1) overflows.
+pub trait FixedRoot: Sized {
+    /// Calculates the nth root of x, where x and the result are scaled by `scale`, rounded
+    /// according to `rounding`.
+    ///
+    /// ### Panics
+    /// This method will panic if n is 0, the scale is not positive, x is negative and n is even,
+    /// or x * scale^(n-1) does not fit in a U256.
+    fn fixed_nth_root(&self, env: &Env, n: u32, scale: &Self, rounding: Rounding) -> Self;
+
+    /// Calculates the cube root of x, where x and the result are scaled by `scale`, rounded
+    /// according to `rounding`. Equivalent to `fixed_nth_root` with n = 3.
+    ///
+    /// ### Panics
+    /// This method will panic if the scale is not positive, or x * scale^2 does not fit in a
+    /// U256.
+    fn fixed_cbrt(&self, env: &Env, scale: &Self, rounding: Rounding) -> Self;
+}
+
+impl FixedRoot for u128 {
+    fn fixed_nth_root(&self, env: &Env, n: u32, scale: &u128, rounding: Rounding) -> u128 {
+        scaled_nth_root(env, *self, n, *scale, rounding)
+    }
+
+    fn fixed_cbrt(&self, env: &Env, scale: &u128, rounding: Rounding) -> u128 {
+        scaled_nth_root(env, *self, 3, *scale, rounding)
+    }
+}
+
+impl FixedRoot for i128 {
+    fn fixed_nth_root(&self, env: &Env, n: u32, scale: &i128, rounding: Rounding) -> i128 {
+        signed_nth_root(env, *self, n, *scale, rounding)
+    }
+
+    fn fixed_cbrt(&self, env: &Env, scale: &i128, rounding: Rounding) -> i128 {
+        signed_nth_root(env, *self, 3, *scale, rounding)
+    }
+}
+
+/// Performs the nth root of x * scale^(n-1) for a signed x, where a negative x has a negative root
+fn signed_nth_root(env: &Env, x: i128, n: u32, scale: i128, rounding: Rounding) -> i128 {
+    if scale <= 0 {
+        panic!("scale not positive");
+    }
+    if x >= 0 {
+        let root = scaled_nth_root(env, x as u128, n, scale as u128, rounding);
+        return i128::try_from(root).unwrap_optimized();
+    }
+    if n.is_multiple_of(2) {
+        panic!("even root of a negative number");
+    }
+    // root(-x) = -root(x), so the rounding direction of the magnitude is reversed
+    let magnitude_rounding = match rounding {
+        Rounding::Floor => Rounding::Ceil,
+        Rounding::Ceil => Rounding::Floor,
+    };
+    let root = scaled_nth_root(env, x.unsigned_abs(), n, scale as u128, magnitude_rounding);
+    // the root is at most 2^127, which wraps to i128::MIN
+    (root as i128).wrapping_neg()
+}
+
+/// Performs the nth root of x * scale^(n-1)
+fn scaled_nth_root(env: &Env, x: u128, n: u32, scale: u128, rounding: Rounding) -> u128 {
+    if n == 0 {
+        panic!("zeroth root");
+    }
+    if scale == 0 {
+        panic!("scale not positive");
+    }
+    let native = scale
+        .checked_pow(n - 1)
+        .and_then(|scale_pow| x.checked_mul(scale_pow));
+    match native {
+        Some(r) => {
+            let root = nth_root_floor(r, n);
+            // the root is at most r, so root^n never overflows
+            if rounding == Rounding::Ceil && root.pow(n) != r {
+                root + 1
+            } else {
+                root
+            }
+        }
+        None => {
+            // scale to U256 and retry
+            let scale_pow = u256::from_u128(env, scale).pow(n - 1);
+            let r = u256::from_u128(env, x).mul(&scale_pow);
+            // the root is at most max(x, scale), so it always fits in a u128
+            u256::to_u128(&nth_root(env, &r, n, rounding)).unwrap_optimized()
+        }
+    }
+}
+
+/// Performs floor(root_n(r)) for n > 0
+fn nth_root_floor(r: u128, n: u32) -> u128 {
+    if n == 1 || r == 0 {
+        return r;
+    }
+    let bits = 128 - r.leading_zeros();
+    if n >= bits {
+        // 1 <= r < 2^n, so the root is 1
+        return 1;
+    }
+    // start from an estimate at least as large as the root, so Newton's method converges downward
+    let mut z: u128 = 1 << bits.div_ceil(n);
+    loop {
+        // the next estimate, ((n-1) * z + q) / n, does not decrease if q >= z
+        let q = div_pow(r, z, n - 1);
+        if q >= z {
+            return z;
+        }
+        // q < z and z is at most 2^64, so this does not overflow
+        z = ((n - 1) as u128 * z + q) / n as u128;
+    }
+}
+
+/// Performs floor(r / z^k)
+fn div_pow(r: u128, z: u128, k: u32) -> u128 {
+    if k == 2 {
+        // z^2 only overflows if it is larger than r
+        return z.checked_mul(z).map_or(0, |z_sq| r / z_sq);
+    }
+    let mut q = r;
+    for _ in 0..k {
+        if q == 0 {
+            break;
+        }
+        q /= z;
+    }
+    q
+}
+
+impl FixedRoot for U256 {
+    fn fixed_nth_root(&self, env: &Env, n: u32, scale: &U256, rounding: Rounding) -> U256 {
+        if n == 0 {
+            panic!("zeroth root");
+        }
+        if *scale == U256::from_u32(env, 0) {
+            panic!("scale not positive");
+        }
+        let r = self.mul(&scale.pow(n - 1));
+        nth_root(env, &r, n, rounding)
+    }
+
+    fn fixed_cbrt(&self, env: &Env, scale: &U256, rounding: Rounding) -> U256 {
+        self.fixed_nth_root(env, 3, scale, rounding)
+    }
+}
+
+/// Performs root_n(r) for n > 0, rounded according to `rounding`
+fn nth_root(env: &Env, r: &U256, n: u32, rounding: Rounding) -> U256 {
+    let root = nth_root_floor_u256(env, r, n);
+    // the root is at most r, so root^n never overflows
+    if rounding == Rounding::Ceil && root.pow(n) != *r {
+        root.add(&U256::from_u32(env, 1))
+    } else {
+        root
+    }
+}
+
+/// Performs floor(root_n(r)) for n > 0
+fn nth_root_floor_u256(env: &Env, r: &U256, n: u32) -> U256 {
+    let bits = u256::bit_length(env, r);
+    if n == 1 || bits == 0 {
+        return r.clone();
+    }
+    if n >= bits {
+        // 1 <= r < 2^n, so the root is 1
+        return U256::from_u32(env, 1);
+    }
+    // start from an estimate at least as large as the root, so Newton's method converges downward
+    let mut z = U256::from_u32(env, 1).shl(bits.div_ceil(n));
+    let n_minus_1 = u256::from_u128(env, (n - 1) as u128);
+    let n_u256 = u256::from_u128(env, n as u128);
+    loop {
+        // the next estimate, ((n-1) * z + q) / n, does not decrease if q >= z
+        let q = div_pow_u256(env, r, &z, n - 1);
+        if q >= z {
+            return z;
+        }
+        z = n_minus_1.mul(&z).add(&q).div(&n_u256);
+    }
+}
+
+/// Performs floor(r / z^k)
+fn div_pow_u256(env: &Env, r: &U256, z: &U256, k: u32) -> U256 {
+    let zero = U256::from_u32(env, 0);
+    if k == 2 {
+        // z is at most 2^86 for a cube root, so z^2 does not overflow
+        return r.div(&z.mul(z));
+    }
+    let mut q = r.clone();
+    for _ in 0..k {
+        if q == zero {
+            break;
+        }
+        q = q.div(z);
+    }
+    q
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCALE_18: u128 = 1_000_000_000_000_000_000;
+
+    /********** u128 **********/
+
+    #[test]
+    fn test_fixed_cbrt_u128() {
+        let env = Env::default();
+        let x: u128 = 27_0000000;
+
+        assert_eq!(x.fixed_cbrt(&env, &1_0000000, Rounding::Floor), 3_0000000);
+        assert_eq!(x.fixed_cbrt(&env, &1_0000000, Rounding::Ceil), 3_0000000);
+    }
+
+    #[test]
+    fn test_fixed_cbrt_u128_rounding() {
+        let env = Env::default();
+        let x: u128 = 2 * SCALE_18;
+
+        // cbrt(2) = 1.259921049894873164767...
+        assert_eq!(
+            x.fixed_cbrt(&env, &SCALE_18, Rounding::Floor),
+            1_259_921_049_894_873_164
+        );
+        assert_eq!(
+            x.fixed_cbrt(&env, &SCALE_18, Rounding::Ceil),
+            1_259_921_049_894_873_165
+        );
+    }
+
+    #[test]
+    fn test_fixed_nth_root_u128() {
+        let env = Env::default();
+
+        // 2^(1/5) = 1.148698354997035006798...
+        assert_eq!(
+            2_0000000u128.fixed_nth_root(&env, 5, &1_0000000, Rounding::Floor),
+            1_1486983
+        );
+        assert_eq!(
+            2_0000000u128.fixed_nth_root(&env, 5, &1_0000000, Rounding::Ceil),
+            1_1486984
+        );
+        assert_eq!(5u128.fixed_nth_root(&env, 1, &SCALE_18, Rounding::Floor), 5);
+        assert_eq!(0u128.fixed_nth_root(&env, 4, &SCALE_18, Rounding::Ceil), 0);
+    }
+
+    #[test]
+    fn test_fixed_nth_root_u128_integer() {
+        let env = Env::default();
+
+        assert_eq!(
+            u128::MAX.fixed_nth_root(&env, 2, &1, Rounding::Floor),
+            u64::MAX as u128
+        );
+        assert_eq!(
+            u128::MAX.fixed_nth_root(&env, 2, &1, Rounding::Ceil),
+            1 << 64
+        );
+        assert_eq!(u128::MAX.fixed_nth_root(&env, 200, &1, Rounding::Floor), 1);
+        assert_eq!(
+            (1u128 << 126).fixed_nth_root(&env, 3, &1, Rounding::Floor),
+            1 << 42
+        );
+    }
+
+    #[test]
+    fn test_fixed_nth_root_u128_large_n() {
+        let env = Env::default();
+
+        assert_eq!(
+            u128::MAX.fixed_nth_root(&env, u32::MAX, &1, Rounding::Floor),
+            1
+        );
+        assert_eq!(
+            u128::MAX.fixed_nth_root(&env, u32::MAX, &1, Rounding::Ceil),
+            2
+        );
+        assert_eq!(1u128.fixed_nth_root(&env, 128, &1, Rounding::Ceil), 1);
+    }
+
+    #[test]
+    fn test_fixed_cbrt_u128_phantom_overflow_scales() {
+        let env = Env::default();
+
+        let result = u128::MAX.fixed_cbrt(&env, &SCALE_18, Rounding::Floor);
+
+        // cbrt(340282366920938463463.374607431768211455) = 6981463.658331559092288464...
+        assert_eq!(result, 6_981_463_658_331_559_092_288_464);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fixed_nth_root_u128_overflow() {
+        let env = Env::default();
+
+        // 2 * 10^18 * (10^18)^4 does not fit in a U256
+        (2 * SCALE_18).fixed_nth_root(&env, 5, &SCALE_18, Rounding::Floor);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fixed_nth_root_u128_zeroth_root() {
+        let env = Env::default();
+
+        SCALE_18.fixed_nth_root(&env, 0, &SCALE_18, Rounding::Floor);
+    }
+
+    /********** i128 **********/
+
+    #[test]
+    fn test_fixed_cbrt_i128_negative() {
+        let env = Env::default();
+        let x: i128 = -2_000_000_000_000_000_000;
+        let scale: i128 = SCALE_18 as i128;
+
+        assert_eq!(
+            x.fixed_cbrt(&env, &scale, Rounding::Floor),
+            -1_259_921_049_894_873_165
+        );
+        assert_eq!(
+            x.fixed_cbrt(&env, &scale, Rounding::Ceil),
+            -1_259_921_049_894_873_164
+        );
+        assert_eq!(
+            (-x).fixed_cbrt(&env, &scale, Rounding::Floor),
+            1_259_921_049_894_873_164
+        );
+    }
+
+    #[test]
+    fn test_fixed_nth_root_i128_bounds() {
+        let env = Env::default();
+
+        assert_eq!(
+            i128::MIN.fixed_nth_root(&env, 1, &1, Rounding::Floor),
+            i128::MIN
+        );
+        assert_eq!(i128::MIN.fixed_nth_root(&env, 127, &1, Rounding::Floor), -2);
+        assert_eq!(
+            i128::MAX.fixed_nth_root(&env, 1, &1, Rounding::Ceil),
+            i128::MAX
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "even root of a negative number")]
+    fn test_fixed_nth_root_i128_even_root_of_negative() {
+        let env = Env::default();
+
+        (-1i128).fixed_nth_root(&env, 2, &1, Rounding::Floor);
+    }
+
+    #[test]
+    #[should_panic(expected = "scale not positive")]
+    fn test_fixed_cbrt_i128_negative_scale() {
+        let env = Env::default();
+
+        1i128.fixed_cbrt(&env, &-1, Rounding::Floor);
+    }
+
+    /********** U256 **********/
+
+    #[test]
+    fn test_fixed_cbrt_u256() {
+        let env = Env::default();
+        let x = U256::from_u128(&env, 2 * SCALE_18);
+        let scale = U256::from_u128(&env, SCALE_18);
+
+        assert_eq!(
+            x.fixed_cbrt(&env, &scale, Rounding::Floor),
+            U256::from_u128(&env, 1_259_921_049_894_873_164)
+        );
+        assert_eq!(
+            x.fixed_cbrt(&env, &scale, Rounding::Ceil),
+            U256::from_u128(&env, 1_259_921_049_894_873_165)
+        );
+    }
+
+    #[test]
+    fn test_fixed_nth_root_u256() {
+        let env = Env::default();
+        let scale = U256::from_u128(&env, SCALE_18);
+
+        // 2^(1/5) = 1.148698354997035006798...
+        assert_eq!(
+            U256::from_u128(&env, 2_000_000_000).fixed_nth_root(
+                &env,
+                5,
+                &U256::from_u128(&env, 1_000_000_000),
+                Rounding::Floor
+            ),
+            U256::from_u128(&env, 1_148_698_354)
+        );
+        assert_eq!(
+            U256::from_u128(&env, 81 * SCALE_18).fixed_nth_root(&env, 4, &scale, Rounding::Ceil),
+            U256::from_u128(&env, 3 * SCALE_18)
+        );
+    }
+
+    #[test]
+    fn test_fixed_nth_root_u256_max() {
+        let env = Env::default();
+        let max = U256::from_parts(&env, u64::MAX, u64::MAX, u64::MAX, u64::MAX);
+        let one = U256::from_u32(&env, 1);
+
+        let sqrt = max.fixed_nth_root(&env, 2, &one, Rounding::Floor);
+        let cbrt = max.fixed_cbrt(&env, &one, Rounding::Ceil);
+
+        assert_eq!(sqrt, U256::from_u128(&env, u128::MAX));
+        // cbrt(2^256 - 1) = 48740834812604276470692694.88...
+        assert_eq!(
+            cbrt,
+            U256::from_u128(&env, 48_740_834_812_604_276_470_692_695)
+        );
+    }
+
+    #[test]
+    fn test_fixed_nth_root_u256_large_n() {
+        let env = Env::default();
+        let max = U256::from_parts(&env, u64::MAX, u64::MAX, u64::MAX, u64::MAX);
+        let one = U256::from_u32(&env, 1);
+
+        let floor = max.fixed_nth_root(&env, u32::MAX, &one, Rounding::Floor);
+        let ceil = max.fixed_nth_root(&env, 256, &one, Rounding::Ceil);
+
+        assert_eq!(floor, one);
+        assert_eq!(ceil, U256::from_u32(&env, 2));
+    }
+}
@@ -0,0 +1,8 @@
+/// The direction to round the result of a fixed point calculation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Round towards negative infinity
+    Floor,
+    /// Round towards positive infinity
+    Ceil,
+}
@@ -0,0 +1,173 @@
+use soroban_sdk::{Env, I256, U256};
+
+use crate::{i256, u256};
+
+// @dev - common fixed point scales. `WAD` and `RAY` follow the naming used by Maker and Aave for
+// 18 and 27 decimals. The constants are `i128`, as most of the crate works with `i128`. Use
+// `Scale::pow10` or `SorobanScale::pow10` for other types or a decimals value known at runtime.
+
+/// 10^7, the scale of a value with 7 decimals, such as a Stellar asset
+pub const SCALE_7: i128 = 1_0000000;
+
+/// 10^9, the scale of a value with 9 decimals
+pub const SCALE_9: i128 = 1_000_000_000;
+
+/// 10^12, the scale of a value with 12 decimals
+pub const SCALE_12: i128 = 1_000_000_000_000;
+
+/// 10^18, the scale of a value with 18 decimals
+pub const SCALE_18: i128 = 1_000_000_000_000_000_000;
+
+/// 10^18, a "wad"
+pub const WAD: i128 = SCALE_18;
+
+/// 10^27, a "ray"
+pub const RAY: i128 = 1_000_000_000_000_000_000_000_000_000;
+
+/// The scale of a fixed point number with a given number of decimals, for native integer types.
+pub trait Scale: Sized {
+    /// The largest number of decimals where 10^decimals fits in Self
+    const MAX_DECIMALS: u32;
+
+    /// Returns 10^decimals, or None if `decimals` is greater than `MAX_DECIMALS`.
+    fn pow10(decimals: u32) -> Option<Self>;
+}
+
+macro_rules! impl_scale {
+    ($t:ty, $max_decimals:expr) => {
+        impl Scale for $t {
+            const MAX_DECIMALS: u32 = $max_decimals;
+
+            fn pow10(decimals: u32) -> Option<Self> {
+                if decimals > Self::MAX_DECIMALS {
+                    return None;
+                }
+                Some((10 as $t).pow(decimals))
+            }
+        }
+    };
+}
+
+impl_scale!(i64, 18);
+impl_scale!(u64, 19);
+impl_scale!(i128, 38);
+impl_scale!(u128, 38);
+
+/// The scale of a fixed point number with a given number of decimals, for Soroban host types.
+pub trait SorobanScale: Sized {
+    /// The largest number of decimals where 10^decimals fits in Self
+    const MAX_DECIMALS: u32;
+
+    /// Returns 10^decimals, or None if `decimals` is greater than `MAX_DECIMALS`.
+    fn pow10(env: &Env, decimals: u32) -> Option<Self>;
+}
+
+impl SorobanScale for I256 {
+    const MAX_DECIMALS: u32 = 76;
+
+    fn pow10(env: &Env, decimals: u32) -> Option<Self> {
+        if decimals > Self::MAX_DECIMALS {
+            return None;
+        }
+        // multiply in chunks of up to 10^37, each of which fits in an i128
+        let mut result = i256::from_i128(env, 10i128.pow(decimals.min(37)));
+        let mut remaining = decimals.saturating_sub(37);
+        while remaining > 0 {
+            let chunk = remaining.min(37);
+            result = result.mul(&i256::from_i128(env, 10i128.pow(chunk)));
+            remaining -= chunk;
+        }
+        Some(result)
+    }
+}
+
+impl SorobanScale for U256 {
+    const MAX_DECIMALS: u32 = 77;
+
+    fn pow10(env: &Env, decimals: u32) -> Option<Self> {
+        if decimals > Self::MAX_DECIMALS {
+            return None;
+        }
+        // multiply in chunks of up to 10^38, the largest power of 10 that fits in a u128
+        let mut result = u256::from_u128(env, 10u128.pow(decimals.min(38)));
+        let mut remaining = decimals.saturating_sub(38);
+        while remaining > 0 {
+            let chunk = remaining.min(38);
+            result = result.mul(&u256::from_u128(env, 10u128.pow(chunk)));
+            remaining -= chunk;
+        }
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /********** constants **********/
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(SCALE_7, 10i128.pow(7));
+        assert_eq!(SCALE_7, crate::STROOP as i128);
+        assert_eq!(SCALE_9, 10i128.pow(9));
+        assert_eq!(SCALE_12, 10i128.pow(12));
+        assert_eq!(SCALE_18, 10i128.pow(18));
+        assert_eq!(WAD, 10i128.pow(18));
+        assert_eq!(RAY, 10i128.pow(27));
+    }
+
+    /********** Scale **********/
+
+    #[test]
+    fn test_pow10() {
+        assert_eq!(i64::pow10(0), Some(1));
+        assert_eq!(i64::pow10(7), Some(SCALE_7 as i64));
+        assert_eq!(u128::pow10(27), Some(RAY as u128));
+    }
+
+    #[test]
+    fn test_pow10_bounds() {
+        assert_eq!(i64::pow10(18), Some(10i64.pow(18)));
+        assert_eq!(i64::pow10(19), None);
+        assert_eq!(u64::pow10(19), Some(10u64.pow(19)));
+        assert_eq!(u64::pow10(20), None);
+        assert_eq!(i128::pow10(38), Some(10i128.pow(38)));
+        assert_eq!(i128::pow10(39), None);
+        assert_eq!(u128::pow10(38), Some(10u128.pow(38)));
+        assert_eq!(u128::pow10(39), None);
+    }
+
+    /********** SorobanScale **********/
+
+    #[test]
+    fn test_pow10_i256() {
+        let env = Env::default();
+
+        assert_eq!(I256::pow10(&env, 18), Some(I256::from_i128(&env, SCALE_18)));
+        assert_eq!(
+            I256::pow10(&env, 38),
+            Some(I256::from_i128(&env, 10).pow(38))
+        );
+        assert_eq!(
+            I256::pow10(&env, 76),
+            Some(I256::from_i128(&env, 10).pow(76))
+        );
+        assert_eq!(I256::pow10(&env, 77), None);
+    }
+
+    #[test]
+    fn test_pow10_u256() {
+        let env = Env::default();
+
+        assert_eq!(
+            U256::pow10(&env, 38),
+            Some(U256::from_u128(&env, 10u128.pow(38)))
+        );
+        assert_eq!(
+            U256::pow10(&env, 77),
+            Some(U256::from_u128(&env, 10).pow(77))
+        );
+        assert_eq!(U256::pow10(&env, 78), None);
+    }
+}
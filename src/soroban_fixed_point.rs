@@ -35,3 +35,61 @@ pub trait SorobanFixedPoint: Sized {
     /// the result does not fit in Self.
     fn fixed_div_ceil(&self, env: &Env, y: &Self, denominator: &Self) -> Self;
 }
+
+/// Additional fixed point calculations with Soroban host objects, for fees and binary fixed point
+/// numbers.
+///
+/// These live outside of `SorobanFixedPoint` so implementing it does not require them. Like
+/// `SorobanFixedPoint`, this trait will panic instead of returning an error.
+pub trait SorobanFixedPointExt: SorobanFixedPoint {
+    /// Safely calculates the smallest gross amount that leaves at least x after a fee of
+    /// `fee_rate` is taken, or ceil(x * scale / (scale - fee_rate)).
+    ///
+    /// This is the exact inverse of `net_down`, such that `net_down` of the result always
+    /// returns x for a non-negative x.
+    ///
+    /// ### Panics
+    /// This method will panic if the fee rate is not within [0, scale), a phantom overflow occurs, or
+    /// the result does not fit in Self.
+    fn gross_up(&self, env: &Env, fee_rate: &Self, scale: &Self) -> Self;
+
+    /// Safely calculates the amount remaining after a fee of `fee_rate` is taken from x, or
+    /// floor(x * (scale - fee_rate) / scale). The fee taken, `x - net`, is rounded up.
+    ///
+    /// ### Panics
+    /// This method will panic if the fee rate is not within [0, scale), a phantom overflow occurs, or
+    /// the result does not fit in Self.
+    fn net_down(&self, env: &Env, fee_rate: &Self, scale: &Self) -> Self;
+
+    /// Safely calculates floor(x * y / 2^shift), for binary fixed point numbers with `shift`
+    /// fractional bits (e.g. Q64.64).
+    ///
+    /// ### Panics
+    /// This method will panic if the shift is not less than 256, a phantom overflow occurs, or
+    /// the result does not fit in Self.
+    fn fixed_mul_shr_floor(&self, env: &Env, y: &Self, shift: u32) -> Self;
+
+    /// Safely calculates ceil(x * y / 2^shift), for binary fixed point numbers with `shift`
+    /// fractional bits (e.g. Q64.64).
+    ///
+    /// ### Panics
+    /// This method will panic if the shift is not less than 256, a phantom overflow occurs, or
+    /// the result does not fit in Self.
+    fn fixed_mul_shr_ceil(&self, env: &Env, y: &Self, shift: u32) -> Self;
+
+    /// Safely calculates floor(x * 2^shift / y), for binary fixed point numbers with `shift`
+    /// fractional bits (e.g. Q64.64).
+    ///
+    /// ### Panics
+    /// This method will panic if y is 0, the shift is not less than 256, a phantom overflow
+    /// occurs, or the result does not fit in Self.
+    fn fixed_div_shl_floor(&self, env: &Env, y: &Self, shift: u32) -> Self;
+
+    /// Safely calculates ceil(x * 2^shift / y), for binary fixed point numbers with `shift`
+    /// fractional bits (e.g. Q64.64).
+    ///
+    /// ### Panics
+    /// This method will panic if y is 0, the shift is not less than 256, a phantom overflow
+    /// occurs, or the result does not fit in Self.
+    fn fixed_div_shl_ceil(&self, env: &Env, y: &Self, shift: u32) -> Self;
+}
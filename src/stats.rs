@@ -0,0 +1,370 @@
+use soroban_sdk::{unwrap::UnwrapOptimized, Env};
+
+use crate::{log_exp, scale::SCALE_18, SorobanFixedPoint};
+
+/// sqrt(2) as a fixed point number with 18 decimals
+pub const SQRT_2: i128 = 1_414_213_562_373_095_049;
+
+/// 1 / sqrt(pi) as a fixed point number with 18 decimals
+const INV_SQRT_PI: i128 = 564_189_583_547_756_287;
+
+/// 2 / sqrt(pi) as a fixed point number with 18 decimals
+const TWO_INV_SQRT_PI: i128 = 1_128_379_167_095_512_574;
+
+/// 1 / sqrt(2 * pi) as a fixed point number with 18 decimals
+const INV_SQRT_2_PI: i128 = 398_942_280_401_432_678;
+
+/// The input where erf switches from the series to the continued fraction
+const ERF_SERIES_LIMIT: i128 = 2 * SCALE_18;
+
+/// The input where erf rounds to 1 and the normal pdf rounds to 0
+const TAIL_LIMIT: i128 = 10 * SCALE_18;
+
+/// The number of terms evaluated for the erfc continued fraction
+const ERFC_TERMS: i128 = 60;
+
+// @dev - all functions operate on fixed point numbers with 18 decimals.
+//
+// erf(x) for |x| < 2 is computed with the series erf(x) = 2/sqrt(pi) * e^(-x^2) * sum(x * (2x^2)^n / (2n+1)!!),
+// which has only positive terms. For |x| >= 2, erfc(x) is computed with the continued fraction
+// erfc(x) = e^(-x^2)/sqrt(pi) * 1/(x + (1/2)/(x + 1/(x + (3/2)/(x + ...)))), which keeps the relative precision
+// of small tail probabilities. More detail can be found here: https://dlmf.nist.gov/7.9
+//
+// Precision:
+// * erf, normal_cdf, and normal_pdf have an absolute error of at most ~1e-16
+// * black_scholes_call and black_scholes_put have a relative error of at most ~1e-14 of the spot and strike
+//
+// Results are rounded down, but the error of `ln` and `exp` means they are not guaranteed to be a lower bound.
+// Contracts that require a bound should apply a margin of at least the error above.
+
+/// Calculates the error function, erf(x).
+pub fn erf(env: &Env, x: i128) -> i128 {
+    if x <= -TAIL_LIMIT {
+        return -SCALE_18;
+    }
+    if x < 0 {
+        return -erf(env, -x);
+    }
+    if x < ERF_SERIES_LIMIT {
+        erf_series(env, x)
+    } else {
+        SCALE_18 - erfc_continued_fraction(env, x)
+    }
+}
+
+/// Calculates the cumulative distribution function of the standard normal distribution at x,
+/// or (1 + erf(x / sqrt(2))) / 2.
+pub fn normal_cdf(env: &Env, x: i128) -> i128 {
+    let z = x.fixed_div_floor(env, &SQRT_2, &SCALE_18);
+    // evaluate the lower tail with erfc to keep precision for small probabilities
+    erfc(env, -z) / 2
+}
+
+/// Calculates the probability density function of the standard normal distribution at x,
+/// or e^(-x^2 / 2) / sqrt(2 * pi).
+pub fn normal_pdf(env: &Env, x: i128) -> i128 {
+    if x.unsigned_abs() >= TAIL_LIMIT as u128 {
+        return 0;
+    }
+    let exponent = x.fixed_mul_floor(env, &x, &SCALE_18) / 2;
+    let power = log_exp::exp(-exponent).unwrap_optimized();
+    power.fixed_mul_floor(env, &INV_SQRT_2_PI, &SCALE_18)
+}
+
+/// Calculates the Black-Scholes price of a European call option.
+///
+/// The `rate` and `volatility` are annualized, and `time` is the time to expiry in years. If the
+/// volatility or time is 0, the result is the intrinsic value of the discounted forward.
+///
+/// ### Panics
+/// If the spot or strike is not positive, the volatility or time is negative, or an overflow occurs
+pub fn black_scholes_call(
+    env: &Env,
+    spot: i128,
+    strike: i128,
+    rate: i128,
+    volatility: i128,
+    time: i128,
+) -> i128 {
+    let discounted_strike = discounted_strike(env, spot, strike, rate, volatility, time);
+    let call = match d1_d2(env, spot, strike, rate, volatility, time) {
+        // C = S * N(d1) - K * e^(-rT) * N(d2)
+        Some((d1, d2)) => {
+            spot.fixed_mul_floor(env, &normal_cdf(env, d1), &SCALE_18)
+                - discounted_strike.fixed_mul_ceil(env, &normal_cdf(env, d2), &SCALE_18)
+        }
+        None => spot - discounted_strike,
+    };
+    call.max(0)
+}
+
+/// Calculates the Black-Scholes price of a European put option.
+///
+/// The `rate` and `volatility` are annualized, and `time` is the time to expiry in years. If the
+/// volatility or time is 0, the result is the intrinsic value of the discounted forward.
+///
+/// ### Panics
+/// If the spot or strike is not positive, the volatility or time is negative, or an overflow occurs
+pub fn black_scholes_put(
+    env: &Env,
+    spot: i128,
+    strike: i128,
+    rate: i128,
+    volatility: i128,
+    time: i128,
+) -> i128 {
+    let discounted_strike = discounted_strike(env, spot, strike, rate, volatility, time);
+    let put = match d1_d2(env, spot, strike, rate, volatility, time) {
+        // P = K * e^(-rT) * N(-d2) - S * N(-d1)
+        Some((d1, d2)) => {
+            discounted_strike.fixed_mul_floor(env, &normal_cdf(env, -d2), &SCALE_18)
+                - spot.fixed_mul_ceil(env, &normal_cdf(env, -d1), &SCALE_18)
+        }
+        None => discounted_strike - spot,
+    };
+    put.max(0)
+}
+
+/// Performs erfc(x) = 1 - erf(x)
+fn erfc(env: &Env, x: i128) -> i128 {
+    if x <= -TAIL_LIMIT {
+        2 * SCALE_18
+    } else if x < 0 {
+        2 * SCALE_18 - erfc(env, -x)
+    } else if x < ERF_SERIES_LIMIT {
+        SCALE_18 - erf_series(env, x)
+    } else {
+        erfc_continued_fraction(env, x)
+    }
+}
+
+/// Performs erf(x) with the series expansion, for 0 <= x < ERF_SERIES_LIMIT
+fn erf_series(env: &Env, x: i128) -> i128 {
+    let x_squared = x.fixed_mul_floor(env, &x, &SCALE_18);
+    let mut sum = x;
+    let mut term = x;
+    let mut n: i128 = 1;
+    loop {
+        // term_n = term_(n-1) * 2x^2 / (2n+1)
+        term = term.fixed_mul_floor(env, &(2 * x_squared), &SCALE_18) / (2 * n + 1);
+        if term == 0 {
+            break;
+        }
+        sum += term;
+        n += 1;
+    }
+    let power = log_exp::exp(-x_squared).unwrap_optimized();
+    sum.fixed_mul_floor(env, &power, &SCALE_18)
+        .fixed_mul_floor(env, &TWO_INV_SQRT_PI, &SCALE_18)
+}
+
+/// Performs erfc(x) with the continued fraction, for x >= ERF_SERIES_LIMIT
+fn erfc_continued_fraction(env: &Env, x: i128) -> i128 {
+    if x >= TAIL_LIMIT {
+        return 0;
+    }
+    // evaluate the fraction from the last term up
+    let mut fraction = x;
+    for k in (1..=ERFC_TERMS).rev() {
+        fraction = x + (k * SCALE_18 / 2).fixed_div_floor(env, &fraction, &SCALE_18);
+    }
+    let x_squared = x.fixed_mul_floor(env, &x, &SCALE_18);
+    let power = log_exp::exp(-x_squared).unwrap_optimized();
+    power
+        .fixed_mul_floor(env, &INV_SQRT_PI, &SCALE_18)
+        .fixed_div_floor(env, &fraction, &SCALE_18)
+}
+
+/// Performs K * e^(-rT), and validates the option parameters
+fn discounted_strike(
+    env: &Env,
+    spot: i128,
+    strike: i128,
+    rate: i128,
+    volatility: i128,
+    time: i128,
+) -> i128 {
+    if spot <= 0 || strike <= 0 || volatility < 0 || time < 0 {
+        panic!("invalid option parameters");
+    }
+    let exponent = rate.fixed_mul_floor(env, &time, &SCALE_18);
+    let discount = log_exp::exp(-exponent).unwrap_optimized();
+    strike.fixed_mul_floor(env, &discount, &SCALE_18)
+}
+
+/// Performs (d1, d2) for the Black-Scholes formula, or None if the volatility or time is 0
+fn d1_d2(
+    env: &Env,
+    spot: i128,
+    strike: i128,
+    rate: i128,
+    volatility: i128,
+    time: i128,
+) -> Option<(i128, i128)> {
+    let sqrt_time = crate::u128::scaled_sqrt_mul(env, time as u128, SCALE_18 as u128) as i128;
+    let vol_sqrt_time = volatility.fixed_mul_floor(env, &sqrt_time, &SCALE_18);
+    if vol_sqrt_time == 0 {
+        return None;
+    }
+    // d1 = (ln(S / K) + (r + sigma^2 / 2) * T) / (sigma * sqrt(T))
+    let log_moneyness =
+        log_exp::ln(spot).unwrap_optimized() - log_exp::ln(strike).unwrap_optimized();
+    let drift = rate + volatility.fixed_mul_floor(env, &volatility, &SCALE_18) / 2;
+    let numerator = log_moneyness + drift.fixed_mul_floor(env, &time, &SCALE_18);
+    let d1 = numerator.fixed_div_floor(env, &vol_sqrt_time, &SCALE_18);
+    Some((d1, d1 - vol_sqrt_time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Asserts |result - expected| <= tolerance
+    fn assert_approx_eq(result: i128, expected: i128, tolerance: i128) {
+        assert!(
+            (result - expected).abs() <= tolerance,
+            "result: {}, expected: {}",
+            result,
+            expected
+        );
+    }
+
+    /********** erf **********/
+
+    #[test]
+    fn test_erf_reference_table() {
+        let env = Env::default();
+        // (x, erf(x))
+        let table: [(i128, i128); 6] = [
+            (0, 0),
+            (500_000_000_000_000_000, 520_499_877_813_046_538),
+            (SCALE_18, 842_700_792_949_714_869),
+            (1_500_000_000_000_000_000, 966_105_146_475_310_727),
+            (2_500_000_000_000_000_000, 999_593_047_982_555_041),
+            (4 * SCALE_18, 999_999_984_582_742_100),
+        ];
+
+        for (x, expected) in table {
+            assert_approx_eq(erf(&env, x), expected, 100);
+            assert_approx_eq(erf(&env, -x), -expected, 100);
+        }
+    }
+
+    #[test]
+    fn test_erf_tail() {
+        let env = Env::default();
+
+        assert_eq!(erf(&env, 7 * SCALE_18), SCALE_18);
+        assert_eq!(erf(&env, i128::MIN + 1), -SCALE_18);
+    }
+
+    #[test]
+    fn test_erf_extremes() {
+        let env = Env::default();
+
+        assert_eq!(erf(&env, i128::MIN), -SCALE_18);
+        assert_eq!(erf(&env, i128::MAX), SCALE_18);
+    }
+
+    /********** normal_cdf **********/
+
+    #[test]
+    fn test_normal_cdf_reference_table() {
+        let env = Env::default();
+        // (x, N(x))
+        let table: [(i128, i128); 4] = [
+            (0, 500_000_000_000_000_000),
+            (1_960_000_000_000_000_000, 975_002_104_851_779_566),
+            (-3 * SCALE_18, 1_349_898_031_630_095),
+            (-8 * SCALE_18, 622),
+        ];
+
+        for (x, expected) in table {
+            assert_approx_eq(normal_cdf(&env, x), expected, 100);
+        }
+    }
+
+    #[test]
+    fn test_normal_cdf_symmetric() {
+        let env = Env::default();
+        let x: i128 = 1_234_567_890_123_456_789;
+
+        let sum = normal_cdf(&env, x) + normal_cdf(&env, -x);
+
+        assert_approx_eq(sum, SCALE_18, 100);
+    }
+
+    /********** normal_pdf **********/
+
+    #[test]
+    fn test_normal_pdf() {
+        let env = Env::default();
+
+        assert_approx_eq(normal_pdf(&env, 0), INV_SQRT_2_PI, 1);
+        assert_approx_eq(normal_pdf(&env, SCALE_18), 241_970_724_519_143_350, 100);
+        assert_approx_eq(normal_pdf(&env, -2 * SCALE_18), 53_990_966_513_188_052, 100);
+        assert_eq!(normal_pdf(&env, i128::MAX), 0);
+    }
+
+    #[test]
+    fn test_normal_pdf_extremes() {
+        let env = Env::default();
+
+        assert_eq!(normal_pdf(&env, i128::MIN), 0);
+        assert_eq!(normal_pdf(&env, i128::MAX), 0);
+    }
+
+    /********** black_scholes **********/
+
+    #[test]
+    fn test_black_scholes_at_the_money() {
+        let env = Env::default();
+        let spot = 100 * SCALE_18;
+        let rate: i128 = 50_000_000_000_000_000;
+        let volatility: i128 = 200_000_000_000_000_000;
+
+        let call = black_scholes_call(&env, spot, spot, rate, volatility, SCALE_18);
+        let put = black_scholes_put(&env, spot, spot, rate, volatility, SCALE_18);
+
+        assert_approx_eq(call, 10_450_583_572_185_566_782, spot / 10i128.pow(14));
+        assert_approx_eq(put, 5_573_526_022_256_967_691, spot / 10i128.pow(14));
+    }
+
+    #[test]
+    fn test_black_scholes_out_of_the_money() {
+        let env = Env::default();
+        let spot = 2000 * SCALE_18;
+        let strike = 2500 * SCALE_18;
+        let rate: i128 = 30_000_000_000_000_000;
+        let volatility: i128 = 800_000_000_000_000_000;
+        let time: i128 = 250_000_000_000_000_000;
+
+        let call = black_scholes_call(&env, spot, strike, rate, volatility, time);
+        let put = black_scholes_put(&env, spot, strike, rate, volatility, time);
+
+        assert_approx_eq(call, 164_021_485_090_779_094_046, strike / 10i128.pow(14));
+        assert_approx_eq(put, 645_341_622_138_625_170_350, strike / 10i128.pow(14));
+    }
+
+    #[test]
+    fn test_black_scholes_zero_volatility() {
+        let env = Env::default();
+        let spot = 100 * SCALE_18;
+        let rate: i128 = 50_000_000_000_000_000;
+
+        let call = black_scholes_call(&env, spot, spot, rate, 0, SCALE_18);
+        let put = black_scholes_put(&env, spot, spot, rate, 0, SCALE_18);
+
+        // S - K * e^(-rT)
+        assert_approx_eq(call, 4_877_057_549_928_599_091, 100);
+        assert_eq!(put, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid option parameters")]
+    fn test_black_scholes_invalid_strike() {
+        let env = Env::default();
+
+        black_scholes_call(&env, SCALE_18, 0, 0, SCALE_18, SCALE_18);
+    }
+}
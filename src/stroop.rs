@@ -0,0 +1,221 @@
+use core::fmt;
+
+use soroban_sdk::unwrap::UnwrapOptimized;
+
+use crate::{display::DisplayFixed, FixedPoint, Rounding, STROOP};
+
+// @dev - Stellar assets have 7 decimals, so 1 whole token is `STROOP` (10^7) stroops. The functions
+// in this module fix the denominator of the `FixedPoint` functions to `STROOP`, so it is not
+// possible to accidentally multiply or divide with a different scale.
+//
+// Precision:
+// * `mul_7` and `div_7` round according to the `Rounding` argument
+// * `to_whole_floor` rounds towards negative infinity, such that -1 stroop is -1 whole token
+
+/// An integer type that can hold a stroop value. Implemented for `i64` and `i128`.
+pub trait StroopInt: FixedPoint + Copy + Ord {
+    /// 1 whole token, or 10^7 stroops
+    const ONE: Self;
+
+    /// Performs self * rhs, or None if an overflow occurs
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+
+    /// Performs self + rhs, or None if an overflow occurs
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+
+    /// Performs self - rhs, or None if an overflow occurs
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+
+    /// Performs floor(self / rhs) for a positive rhs
+    fn div_floor(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_stroop_int {
+    ($t:ty) => {
+        impl StroopInt for $t {
+            const ONE: Self = STROOP as $t;
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+
+            fn div_floor(self, rhs: Self) -> Self {
+                self.div_euclid(rhs)
+            }
+        }
+    };
+}
+
+impl_stroop_int!(i64);
+impl_stroop_int!(i128);
+
+/// Calculates x * y / 10^7, where x and y have 7 decimals, rounded according to `rounding`.
+///
+/// Returns None if a phantom overflow occurs.
+pub fn mul_7<T: StroopInt>(x: T, y: T, rounding: Rounding) -> Option<T> {
+    match rounding {
+        Rounding::Floor => x.fixed_mul_floor(y, T::ONE),
+        Rounding::Ceil => x.fixed_mul_ceil(y, T::ONE),
+    }
+}
+
+/// Calculates x * 10^7 / y, where x and y have 7 decimals, rounded according to `rounding`.
+///
+/// Returns None if a phantom overflow occurs or y is 0.
+pub fn div_7<T: StroopInt>(x: T, y: T, rounding: Rounding) -> Option<T> {
+    match rounding {
+        Rounding::Floor => x.fixed_div_floor(y, T::ONE),
+        Rounding::Ceil => x.fixed_div_ceil(y, T::ONE),
+    }
+}
+
+/// Converts a number of whole tokens to stroops.
+///
+/// Returns None if an overflow occurs.
+pub fn from_whole<T: StroopInt>(whole: T) -> Option<T> {
+    whole.checked_mul(T::ONE)
+}
+
+/// Converts stroops to a number of whole tokens, rounded towards negative infinity.
+pub fn to_whole_floor<T: StroopInt>(stroops: T) -> T {
+    stroops.div_floor(T::ONE)
+}
+
+/// An amount with 7 decimals, such as the balance of a Stellar asset. Implemented for `i64`
+/// and `i128`.
+///
+/// Formats as a decimal number of whole tokens, e.g. "12.3456789".
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Stroops<T>(pub T);
+
+impl<T: StroopInt> Stroops<T> {
+    /// 1 whole token
+    pub const ONE: Self = Stroops(T::ONE);
+
+    /// Creates an amount of `whole` tokens, or None if an overflow occurs
+    pub fn from_whole(whole: T) -> Option<Self> {
+        from_whole(whole).map(Stroops)
+    }
+
+    /// Returns the number of whole tokens, rounded towards negative infinity
+    pub fn to_whole_floor(self) -> T {
+        to_whole_floor(self.0)
+    }
+
+    /// Returns self * rhs, rounded according to `rounding`, or None if a phantom overflow occurs
+    pub fn mul(self, rhs: Self, rounding: Rounding) -> Option<Self> {
+        mul_7(self.0, rhs.0, rounding).map(Stroops)
+    }
+
+    /// Returns self / rhs, rounded according to `rounding`, or None if a phantom overflow occurs
+    /// or rhs is 0
+    pub fn div(self, rhs: Self, rounding: Rounding) -> Option<Self> {
+        div_7(self.0, rhs.0, rounding).map(Stroops)
+    }
+
+    /// Returns self + rhs, or None if an overflow occurs
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).map(Stroops)
+    }
+
+    /// Returns self - rhs, or None if an overflow occurs
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(Stroops)
+    }
+}
+
+impl fmt::Display for Stroops<i64> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&DisplayFixed::new(self.0, 7).unwrap_optimized(), f)
+    }
+}
+
+impl fmt::Display for Stroops<i128> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&DisplayFixed::new(self.0, 7).unwrap_optimized(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::format;
+
+    /********** mul_7 **********/
+
+    #[test]
+    fn test_mul_7() {
+        let x: i128 = 1_5000000;
+        let y: i128 = 2_0000001;
+
+        assert_eq!(mul_7(x, y, Rounding::Floor), Some(3_0000001));
+        assert_eq!(mul_7(x, y, Rounding::Ceil), Some(3_0000002));
+        assert_eq!(mul_7(-x, y, Rounding::Floor), Some(-3_0000002));
+    }
+
+    #[test]
+    fn test_mul_7_i64_phantom_overflow() {
+        let x: i64 = i64::MAX / 2;
+
+        assert_eq!(mul_7(x, 1_0000000, Rounding::Floor), Some(x));
+        assert_eq!(mul_7(x, 3_0000000, Rounding::Floor), None);
+    }
+
+    /********** div_7 **********/
+
+    #[test]
+    fn test_div_7() {
+        let x: i64 = 1_0000000;
+        let y: i64 = 3_0000000;
+
+        assert_eq!(div_7(x, y, Rounding::Floor), Some(3333333));
+        assert_eq!(div_7(x, y, Rounding::Ceil), Some(3333334));
+        assert_eq!(div_7(x, 0, Rounding::Floor), None);
+    }
+
+    /********** whole **********/
+
+    #[test]
+    fn test_from_whole() {
+        assert_eq!(from_whole(12i64), Some(12_0000000));
+        assert_eq!(from_whole(-3i128), Some(-3_0000000));
+        assert_eq!(from_whole(i64::MAX / 1_0000000 + 1), None);
+    }
+
+    #[test]
+    fn test_to_whole_floor() {
+        assert_eq!(to_whole_floor(12_9999999i64), 12);
+        assert_eq!(to_whole_floor(-1i128), -1);
+        assert_eq!(to_whole_floor(-1_0000000i128), -1);
+    }
+
+    /********** Stroops **********/
+
+    #[test]
+    fn test_stroops() {
+        let price = Stroops::<i128>::from_whole(2).unwrap();
+        let amount = Stroops(1_5000000i128);
+
+        assert_eq!(amount.mul(price, Rounding::Floor), Some(Stroops(3_0000000)));
+        assert_eq!(amount.div(price, Rounding::Ceil), Some(Stroops(7500000)));
+        assert_eq!(amount.checked_add(Stroops::ONE), Some(Stroops(2_5000000)));
+        assert_eq!(Stroops(i64::MIN).checked_sub(Stroops::ONE), None);
+        assert_eq!(amount.to_whole_floor(), 1);
+    }
+
+    #[test]
+    fn test_stroops_display() {
+        assert_eq!(format!("{}", Stroops(-12_3456789i128)), "-12.3456789");
+        assert_eq!(format!("{:.2}", Stroops(5_0000000i64)), "5.00");
+    }
+}
@@ -0,0 +1,306 @@
+use crate::scale::SCALE_18;
+
+/// The maximum number of decimals supported by the trigonometric functions
+pub const MAX_DECIMALS: u32 = 18;
+
+/// pi as a fixed point number with 18 decimals
+pub const PI: i128 = 3_141_592_653_589_793_238;
+
+/// pi / 2 as a fixed point number with 18 decimals
+pub const HALF_PI: i128 = 1_570_796_326_794_896_619;
+
+/// 2 * pi, truncated to 18 decimals
+const TWO_PI_HI: i128 = 6_283_185_307_179_586_476;
+
+/// The next 18 decimals of 2 * pi after `TWO_PI_HI`
+const TWO_PI_LO: i128 = 925_286_766_559_005_768;
+
+/// pi / 6 as a fixed point number with 18 decimals
+const SIXTH_PI: i128 = 523_598_775_598_298_873;
+
+/// sqrt(3) as a fixed point number with 18 decimals
+const SQRT_3: i128 = 1_732_050_807_568_877_294;
+
+/// tan(pi / 12) = 2 - sqrt(3) as a fixed point number with 18 decimals
+const TAN_TWELFTH_PI: i128 = 267_949_192_431_122_706;
+
+// @dev - inputs are scaled to 18 decimals before any calculation, and results are scaled back to `decimals`.
+// All intermediate rounding is toward zero, so `fixed_sin` and `fixed_atan2` are exactly odd functions and
+// `fixed_cos` is exactly even.
+//
+// sin and cos reduce the angle to [-pi, pi] with a 36 decimal approximation of 2 * pi, then to [-pi/2, pi/2]
+// with symmetry, and evaluate the Taylor series of sin. atan2 reduces the ratio of the inputs to [0, 1], then to
+// [0, tan(pi/12)] with atan(t) = pi/6 + atan((sqrt(3) * t - 1) / (sqrt(3) + t)), and evaluates the Taylor series
+// of atan. More detail can be found here: https://en.wikipedia.org/wiki/Trigonometric_functions#Power_series_expansion
+//
+// Precision:
+// * all functions have an absolute error of at most ~1e-17, plus 1 unit at the requested decimals
+// * sin and cos lose an additional ~1e-36 per radian of the input to the range reduction
+
+/// Calculates sin(x), where x is in radians, for fixed point numbers with `decimals` decimals.
+///
+/// Returns None if `decimals` is greater than `MAX_DECIMALS`, or if x is too large to scale to
+/// 18 decimals.
+pub fn fixed_sin(x: i128, decimals: u32) -> Option<i128> {
+    let mut r = reduce_angle(to_scalar_18(x, decimals)?)?;
+    // sin(x) = sin(pi - x)
+    if r > HALF_PI {
+        r = PI - r;
+    } else if r < -HALF_PI {
+        r = -PI - r;
+    }
+    Some(from_scalar_18(sin_series(r), decimals))
+}
+
+/// Calculates cos(x), where x is in radians, for fixed point numbers with `decimals` decimals.
+///
+/// Returns None if `decimals` is greater than `MAX_DECIMALS`, or if x is too large to scale to
+/// 18 decimals.
+pub fn fixed_cos(x: i128, decimals: u32) -> Option<i128> {
+    let r = reduce_angle(to_scalar_18(x, decimals)?)?;
+    // cos(x) = sin(pi/2 - |x|)
+    Some(from_scalar_18(sin_series(HALF_PI - r.abs()), decimals))
+}
+
+/// Calculates the angle in radians between the positive x axis and the point (x, y), within
+/// [-pi, pi], for fixed point numbers with `decimals` decimals. Returns 0 for the point (0, 0).
+///
+/// Returns None if `decimals` is greater than `MAX_DECIMALS`.
+pub fn fixed_atan2(y: i128, x: i128, decimals: u32) -> Option<i128> {
+    if decimals > MAX_DECIMALS {
+        return None;
+    }
+    if x == 0 && y == 0 {
+        return Some(0);
+    }
+    // the scale of the inputs cancels out, so only the ratio min / max is required
+    let abs_x = x.unsigned_abs();
+    let abs_y = y.unsigned_abs();
+    let (mut low, mut high) = if abs_y > abs_x {
+        (abs_x, abs_y)
+    } else {
+        (abs_y, abs_x)
+    };
+    // shift large inputs down so the ratio can be computed without overflow
+    let excess_bits = 64u32.saturating_sub(high.leading_zeros());
+    low >>= excess_bits;
+    high >>= excess_bits;
+    let ratio = (low * SCALE_18 as u128 / high) as i128;
+
+    let mut angle = atan_unit(ratio);
+    if abs_y > abs_x {
+        angle = HALF_PI - angle;
+    }
+    if x < 0 {
+        angle = PI - angle;
+    }
+    if y < 0 {
+        angle = -angle;
+    }
+    Some(from_scalar_18(angle, decimals))
+}
+
+/// Performs x * 10^(18 - decimals)
+fn to_scalar_18(x: i128, decimals: u32) -> Option<i128> {
+    if decimals > MAX_DECIMALS {
+        return None;
+    }
+    x.checked_mul(10i128.pow(MAX_DECIMALS - decimals))
+}
+
+/// Performs x / 10^(18 - decimals), rounding toward zero
+fn from_scalar_18(x: i128, decimals: u32) -> i128 {
+    x / 10i128.pow(MAX_DECIMALS - decimals)
+}
+
+/// Performs x - k * 2pi, with k chosen such that the result is within [-pi, pi]
+fn reduce_angle(x: i128) -> Option<i128> {
+    let half_turn = if x >= 0 {
+        TWO_PI_HI / 2
+    } else {
+        -TWO_PI_HI / 2
+    };
+    let k = x.checked_add(half_turn)? / TWO_PI_HI;
+    // k * 2pi is computed with 36 decimals, so large angles retain their precision
+    let low = k.checked_mul(TWO_PI_LO)? / SCALE_18;
+    Some(x - k * TWO_PI_HI - low)
+}
+
+/// Performs sin(r) with the Taylor series, for r within [-pi/2, pi/2]
+fn sin_series(r: i128) -> i128 {
+    let r_squared = r * r / SCALE_18;
+    let mut sum = r;
+    let mut term = r;
+    let mut n: i128 = 1;
+    loop {
+        // term_n = -term_(n-1) * r^2 / ((2n) * (2n+1))
+        term = -(term * r_squared / SCALE_18) / ((2 * n) * (2 * n + 1));
+        if term == 0 {
+            break;
+        }
+        sum += term;
+        n += 1;
+    }
+    sum
+}
+
+/// Performs atan(t) for t within [0, 1]
+fn atan_unit(t: i128) -> i128 {
+    if t > TAN_TWELFTH_PI {
+        // atan(t) = pi/6 + atan((sqrt(3) * t - 1) / (sqrt(3) + t))
+        let numerator = SQRT_3 * t / SCALE_18 - SCALE_18;
+        let reduced = numerator * SCALE_18 / (SQRT_3 + t);
+        SIXTH_PI + atan_series(reduced)
+    } else {
+        atan_series(t)
+    }
+}
+
+/// Performs atan(t) with the Taylor series, for t within [-tan(pi/12), tan(pi/12)]
+fn atan_series(t: i128) -> i128 {
+    let t_squared = t * t / SCALE_18;
+    let mut sum = t;
+    let mut power = t;
+    let mut n: i128 = 1;
+    loop {
+        // t^(2n+1) * (-1)^n / (2n+1)
+        power = -(power * t_squared / SCALE_18);
+        let term = power / (2 * n + 1);
+        if term == 0 {
+            break;
+        }
+        sum += term;
+        n += 1;
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Asserts |result - expected| <= tolerance
+    fn assert_approx_eq(result: i128, expected: i128, tolerance: i128) {
+        assert!(
+            (result - expected).abs() <= tolerance,
+            "result: {}, expected: {}",
+            result,
+            expected
+        );
+    }
+
+    /********** fixed_sin **********/
+
+    #[test]
+    fn test_fixed_sin_reference_table() {
+        // (x, sin(x))
+        let table: [(i128, i128); 7] = [
+            (0, 0),
+            (500_000_000_000_000_000, 479_425_538_604_203_000),
+            (SCALE_18, 841_470_984_807_896_507),
+            (HALF_PI, SCALE_18),
+            (2 * SCALE_18, 909_297_426_825_681_695),
+            (4 * SCALE_18, -756_802_495_307_928_251),
+            (100 * SCALE_18, -506_365_641_109_758_794),
+        ];
+
+        for (x, expected) in table {
+            assert_approx_eq(fixed_sin(x, 18).unwrap(), expected, 10);
+            assert_eq!(fixed_sin(-x, 18).unwrap(), -fixed_sin(x, 18).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_fixed_sin_large_angle() {
+        // sin(1e9) = 0.545843449448699564...
+        let result = fixed_sin(1_000_000_000 * SCALE_18, 18).unwrap();
+
+        assert_approx_eq(result, 545_843_449_448_699_564, 10);
+    }
+
+    #[test]
+    fn test_fixed_sin_7_decimals() {
+        let result = fixed_sin(1_0000000, 7).unwrap();
+
+        // sin(1) = 0.8414709848...
+        assert_eq!(result, 8414709);
+    }
+
+    #[test]
+    fn test_fixed_sin_invalid_decimals() {
+        assert_eq!(fixed_sin(SCALE_18, 19), None);
+        assert_eq!(fixed_sin(i128::MAX, 17), None);
+    }
+
+    /********** fixed_cos **********/
+
+    #[test]
+    fn test_fixed_cos_reference_table() {
+        // (x, cos(x))
+        let table: [(i128, i128); 6] = [
+            (0, SCALE_18),
+            (500_000_000_000_000_000, 877_582_561_890_372_716),
+            (SCALE_18, 540_302_305_868_139_717),
+            (PI, -SCALE_18),
+            (4 * SCALE_18, -653_643_620_863_611_915),
+            (100 * SCALE_18, 862_318_872_287_683_934),
+        ];
+
+        for (x, expected) in table {
+            assert_approx_eq(fixed_cos(x, 18).unwrap(), expected, 10);
+            assert_eq!(fixed_cos(-x, 18).unwrap(), fixed_cos(x, 18).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_fixed_cos_7_decimals() {
+        let result = fixed_cos(1_0000000, 7).unwrap();
+
+        // cos(1) = 0.5403023058...
+        assert_eq!(result, 5403023);
+    }
+
+    /********** fixed_atan2 **********/
+
+    #[test]
+    fn test_fixed_atan2_reference_table() {
+        // (y, x, atan2(y, x))
+        let table: [(i128, i128, i128); 7] = [
+            (SCALE_18, SCALE_18, 785_398_163_397_448_310),
+            (SCALE_18, 2 * SCALE_18, 463_647_609_000_806_116),
+            (SCALE_18, 10 * SCALE_18, 99_668_652_491_162_027),
+            (3 * SCALE_18, SCALE_18, 1_249_045_772_398_254_426),
+            (SCALE_18, -SCALE_18, 2_356_194_490_192_344_929),
+            (-2 * SCALE_18, -SCALE_18, -2_034_443_935_795_702_735),
+            (0, -SCALE_18, PI),
+        ];
+
+        for (y, x, expected) in table {
+            assert_approx_eq(fixed_atan2(y, x, 18).unwrap(), expected, 10);
+        }
+    }
+
+    #[test]
+    fn test_fixed_atan2_axes() {
+        assert_eq!(fixed_atan2(0, 0, 18).unwrap(), 0);
+        assert_eq!(fixed_atan2(0, SCALE_18, 18).unwrap(), 0);
+        assert_eq!(fixed_atan2(SCALE_18, 0, 18).unwrap(), HALF_PI);
+        assert_eq!(fixed_atan2(-SCALE_18, 0, 18).unwrap(), -HALF_PI);
+    }
+
+    #[test]
+    fn test_fixed_atan2_large_inputs() {
+        let result = fixed_atan2(i128::MAX, i128::MAX, 18).unwrap();
+
+        assert_approx_eq(result, 785_398_163_397_448_310, 10);
+        assert_eq!(fixed_atan2(i128::MIN, 1, 18).unwrap(), -HALF_PI);
+    }
+
+    #[test]
+    fn test_fixed_atan2_7_decimals() {
+        let result = fixed_atan2(1_0000000, 1_0000000, 7).unwrap();
+
+        // pi / 4 = 0.7853981633...
+        assert_eq!(result, 7853981);
+    }
+}
@@ -1,6 +1,9 @@
-use soroban_sdk::{unwrap::UnwrapOptimized, Env, U256};
+use soroban_sdk::{unwrap::UnwrapOptimized, Env};
 
-use crate::{fixed_point::FixedPoint, SorobanFixedPoint};
+use crate::{
+    fixed_point::{fee_complement, FixedPoint},
+    SorobanFixedPoint, SorobanFixedPointExt,
+};
 
 impl FixedPoint for u128 {
     fn fixed_mul_floor(self, y: u128, denominator: u128) -> Option<u128> {
@@ -45,7 +48,7 @@ impl SorobanFixedPoint for u128 {
     }
 
     fn fixed_mul_ceil(&self, env: &Env, y: &u128, denominator: &u128) -> u128 {
-        scaled_mul_div_ceil(&self, env, y, denominator)
+        scaled_mul_div_ceil(self, env, y, denominator)
     }
 
     fn fixed_div_floor(&self, env: &Env, y: &u128, denominator: &u128) -> u128 {
@@ -57,39 +60,148 @@ impl SorobanFixedPoint for u128 {
     }
 }
 
+impl SorobanFixedPointExt for u128 {
+    fn gross_up(&self, env: &Env, fee_rate: &u128, scale: &u128) -> u128 {
+        let complement = fee_complement(*fee_rate, *scale).unwrap_optimized();
+        scaled_mul_div_ceil(self, env, scale, &complement)
+    }
+
+    fn net_down(&self, env: &Env, fee_rate: &u128, scale: &u128) -> u128 {
+        let complement = fee_complement(*fee_rate, *scale).unwrap_optimized();
+        scaled_mul_div_floor(self, env, &complement, scale)
+    }
+
+    fn fixed_mul_shr_floor(&self, env: &Env, y: &u128, shift: u32) -> u128 {
+        scaled_mul_shr_floor(self, env, y, shift)
+    }
+
+    fn fixed_mul_shr_ceil(&self, env: &Env, y: &u128, shift: u32) -> u128 {
+        scaled_mul_shr_ceil(self, env, y, shift)
+    }
+
+    fn fixed_div_shl_floor(&self, env: &Env, y: &u128, shift: u32) -> u128 {
+        scaled_shl_div_floor(self, env, y, shift)
+    }
+
+    fn fixed_div_shl_ceil(&self, env: &Env, y: &u128, shift: u32) -> u128 {
+        scaled_shl_div_ceil(self, env, y, shift)
+    }
+}
+
 /// Performs floor(x * y / z)
 fn scaled_mul_div_floor(x: &u128, env: &Env, y: &u128, z: &u128) -> u128 {
-    return match x.checked_mul(*y) {
+    match x.checked_mul(*y) {
         Some(r) => r.checked_div(*z).unwrap_optimized(),
         None => {
             // scale to U256 and retry
             let res = crate::u256::mul_div_floor(
-                &U256::from_u128(&env, *x),
-                &U256::from_u128(&env, *y),
-                &U256::from_u128(&env, *z),
+                &crate::u256::from_u128(env, *x),
+                &crate::u256::from_u128(env, *y),
+                &crate::u256::from_u128(env, *z),
             );
             // will panic if result is not representable in u128
-            res.to_u128().unwrap_optimized()
+            crate::u256::to_u128(&res).unwrap_optimized()
         }
-    };
+    }
 }
 
 /// Performs floor(x * y / z)
 fn scaled_mul_div_ceil(x: &u128, env: &Env, y: &u128, z: &u128) -> u128 {
-    return match x.checked_mul(*y) {
+    match x.checked_mul(*y) {
         Some(r) => div_ceil(r, *z).unwrap_optimized(),
         None => {
             // scale to U256 and retry
-            let res = crate::u256::mul_div_ceil(
-                &env,
-                &U256::from_u128(&env, *x),
-                &U256::from_u128(&env, *y),
-                &U256::from_u128(&env, *z),
+            let res = crate::u256::mul_div_floor(
+                &crate::u256::from_u128(env, *x),
+                &crate::u256::from_u128(env, *y),
+                &crate::u256::from_u128(env, *z),
             );
             // will panic if result is not representable in u128
-            res.to_u128().unwrap_optimized()
+            let quotient = crate::u256::to_u128(&res).unwrap_optimized();
+            // the remainder is smaller than z < 2^128, so the division is exact if and only if the
+            // low 128 bits of x * y and quotient * z match
+            if x.wrapping_mul(*y) == quotient.wrapping_mul(*z) {
+                quotient
+            } else {
+                quotient.checked_add(1).unwrap_optimized()
+            }
         }
-    };
+    }
+}
+
+/// Performs floor(x * y / 2^shift)
+fn scaled_mul_shr_floor(x: &u128, env: &Env, y: &u128, shift: u32) -> u128 {
+    match x.checked_mul(*y) {
+        Some(r) if shift < 128 => r >> shift,
+        _ => {
+            // scale to U256 and retry
+            let res = crate::u256::mul_shr_floor(
+                &crate::u256::from_u128(env, *x),
+                &crate::u256::from_u128(env, *y),
+                shift,
+            );
+            // will panic if result is not representable in u128
+            crate::u256::to_u128(&res).unwrap_optimized()
+        }
+    }
+}
+
+/// Performs ceil(x * y / 2^shift)
+fn scaled_mul_shr_ceil(x: &u128, env: &Env, y: &u128, shift: u32) -> u128 {
+    match x.checked_mul(*y) {
+        // any bits dropped by the shift are the remainder
+        Some(r) if shift < 128 => (r >> shift) + ((r >> shift) << shift != r) as u128,
+        _ => {
+            // scale to U256 and retry
+            let res = crate::u256::mul_shr_ceil(
+                env,
+                &crate::u256::from_u128(env, *x),
+                &crate::u256::from_u128(env, *y),
+                shift,
+            );
+            // will panic if result is not representable in u128
+            crate::u256::to_u128(&res).unwrap_optimized()
+        }
+    }
+}
+
+/// Performs floor(x * 2^shift / y)
+fn scaled_shl_div_floor(x: &u128, env: &Env, y: &u128, shift: u32) -> u128 {
+    if shift < 128 && x.leading_zeros() >= shift {
+        (x << shift).checked_div(*y).unwrap_optimized()
+    } else {
+        // scale to U256 and retry
+        let r = crate::u256::shl_checked(&crate::u256::from_u128(env, *x), shift);
+        let res = r.div(&crate::u256::from_u128(env, *y));
+        // will panic if result is not representable in u128
+        crate::u256::to_u128(&res).unwrap_optimized()
+    }
+}
+
+/// Performs ceil(x * 2^shift / y)
+fn scaled_shl_div_ceil(x: &u128, env: &Env, y: &u128, shift: u32) -> u128 {
+    if shift < 128 && x.leading_zeros() >= shift {
+        div_ceil(x << shift, *y).unwrap_optimized()
+    } else {
+        // scale to U256 and retry
+        let r = crate::u256::shl_checked(&crate::u256::from_u128(env, *x), shift);
+        let res = crate::u256::div_ceil(env, &r, &crate::u256::from_u128(env, *y));
+        // will panic if result is not representable in u128
+        crate::u256::to_u128(&res).unwrap_optimized()
+    }
+}
+
+/// Performs floor(sqrt(x * y))
+pub(crate) fn scaled_sqrt_mul(env: &Env, x: u128, y: u128) -> u128 {
+    match x.checked_mul(y) {
+        Some(r) => r.isqrt(),
+        None => {
+            // scale to U256 and retry
+            let r = crate::u256::from_u128(env, x).mul(&crate::u256::from_u128(env, y));
+            // the root of the product of two u128 values always fits in a u128
+            crate::u256::to_u128(&crate::u256::sqrt_floor(env, &r)).unwrap_optimized()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -236,11 +348,69 @@ mod test_fixed_point {
 
         assert_eq!(None, result);
     }
+
+    /********** gross_up **********/
+
+    #[test]
+    fn test_gross_up_rounds_up() {
+        // a 25 basis point fee on a value with 18 decimals
+        let net: u128 = 10u128.pow(18);
+
+        let result = net.gross_up(25, 10_000).unwrap();
+
+        assert_eq!(result, 1_002_506_265_664_160_402)
+    }
+
+    #[test]
+    fn test_gross_up_is_inverse_of_net_down() {
+        let net: u128 = 10u128.pow(18);
+
+        let gross = net.gross_up(25, 10_000).unwrap();
+
+        assert_eq!(gross.net_down(25, 10_000).unwrap(), net);
+        assert_eq!((gross - 1).net_down(25, 10_000).unwrap(), net - 1);
+    }
+
+    #[test]
+    fn test_gross_up_phantom_overflow() {
+        // net * scale overflows, and the native implementation does not scale to 256 bits
+        let result = (u128::MAX / 2).gross_up(25, 10_000);
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn test_gross_up_invalid_fee_rate() {
+        let result = 10u128.pow(18).gross_up(10_000, 10_000);
+
+        assert_eq!(result, None);
+    }
+
+    /********** net_down **********/
+
+    #[test]
+    fn test_net_down_rounds_down() {
+        let gross: u128 = 1_002_506_265_664_160_401;
+
+        let result = gross.net_down(25, 10_000).unwrap();
+
+        assert_eq!(result, 999_999_999_999_999_999)
+    }
+
+    #[test]
+    fn test_net_down_max() {
+        let result = u128::MAX.net_down(0, 1);
+
+        assert_eq!(result, Some(u128::MAX))
+    }
 }
 
 #[cfg(test)]
 mod test_soroban_fixed_point {
-    use crate::SorobanFixedPoint;
+    use crate::{
+        budget::{baseline_u128_mul_div_ceil, baseline_u128_mul_div_floor, measure},
+        SorobanFixedPoint, SorobanFixedPointExt,
+    };
     use soroban_sdk::Env;
 
     /********** fixed_mul_floor **********/
@@ -370,4 +540,224 @@ mod test_soroban_fixed_point {
 
         assert_eq!(result, 340_282_366_920_938_463_463 * 10u128.pow(9));
     }
+
+    /********** gross_up **********/
+
+    #[test]
+    fn test_gross_up_rounds_up() {
+        let env = Env::default();
+        let net: u128 = 1_5391283;
+        let fee_rate: u128 = 30000;
+        let scale: u128 = 1_0000000;
+
+        let result = net.gross_up(&env, &fee_rate, &scale);
+
+        assert_eq!(result, 1_5437596);
+    }
+
+    #[test]
+    fn test_gross_up_is_inverse_of_net_down() {
+        let env = Env::default();
+        let net: u128 = 1_5391283;
+        let fee_rate: u128 = 30000;
+        let scale: u128 = 1_0000000;
+
+        let gross = net.gross_up(&env, &fee_rate, &scale);
+
+        assert_eq!(gross.net_down(&env, &fee_rate, &scale), net);
+    }
+
+    #[test]
+    fn test_gross_up_phantom_overflow_scales() {
+        let env = Env::default();
+        let net: u128 = 170_141_183_460_469_231_731;
+        let fee_rate: u128 = 5 * 10u128.pow(17);
+        let scale: u128 = 10u128.pow(18);
+
+        let result = net.gross_up(&env, &fee_rate, &scale);
+
+        assert_eq!(result, 340_282_366_920_938_463_462);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_gross_up_invalid_fee_rate() {
+        let env = Env::default();
+        let net: u128 = 1_5391283;
+        let scale: u128 = 1_0000000;
+
+        net.gross_up(&env, &scale, &scale);
+    }
+
+    /********** net_down **********/
+
+    #[test]
+    fn test_net_down_rounds_down() {
+        let env = Env::default();
+        let gross: u128 = 1_5437596;
+        let fee_rate: u128 = 30000;
+        let scale: u128 = 1_0000000;
+
+        let result = gross.net_down(&env, &fee_rate, &scale);
+
+        assert_eq!(result, 1_5391283);
+    }
+
+    /********** fixed_mul_shr_floor **********/
+
+    #[test]
+    fn test_fixed_mul_shr_floor_q64() {
+        let env = Env::default();
+        // 1.5 * 1.25 = 1.875
+        let x: u128 = 3 << 63;
+        let y: u128 = 5 << 62;
+
+        let result = x.fixed_mul_shr_floor(&env, &y, 64);
+
+        assert_eq!(result, 15 << 61);
+    }
+
+    #[test]
+    fn test_fixed_mul_shr_floor_phantom_overflow_scales() {
+        let env = Env::default();
+        let x: u128 = u128::MAX;
+        let y: u128 = 1 << 64;
+
+        let result = x.fixed_mul_shr_floor(&env, &y, 64);
+
+        assert_eq!(result, u128::MAX);
+    }
+
+    #[test]
+    fn test_fixed_mul_shr_floor_large_shift_scales() {
+        let env = Env::default();
+        let x: u128 = 1 << 100;
+
+        let result = x.fixed_mul_shr_floor(&env, &x, 136);
+
+        assert_eq!(result, 1 << 64);
+    }
+
+    /********** fixed_mul_shr_ceil **********/
+
+    #[test]
+    fn test_fixed_mul_shr_ceil_rounds_up() {
+        let env = Env::default();
+
+        assert_eq!(3u128.fixed_mul_shr_ceil(&env, &1, 1), 2);
+        assert_eq!(4u128.fixed_mul_shr_ceil(&env, &1, 1), 2);
+        assert_eq!(1u128.fixed_mul_shr_ceil(&env, &1, 127), 1);
+    }
+
+    #[test]
+    fn test_fixed_mul_shr_ceil_phantom_overflow_scales() {
+        let env = Env::default();
+        let x: u128 = u128::MAX;
+        let y: u128 = 3;
+
+        let result = x.fixed_mul_shr_ceil(&env, &y, 2);
+
+        assert_eq!(result, (u128::MAX >> 2) * 3 + 3);
+    }
+
+    /********** fixed_div_shl_floor **********/
+
+    #[test]
+    fn test_fixed_div_shl_floor_rounds_down() {
+        let env = Env::default();
+        let x: u128 = 1 << 64;
+        let y: u128 = 3 << 64;
+
+        let result = x.fixed_div_shl_floor(&env, &y, 64);
+
+        assert_eq!(result, 6148914691236517205);
+    }
+
+    #[test]
+    fn test_fixed_div_shl_floor_phantom_overflow_scales() {
+        let env = Env::default();
+        let x: u128 = 1 << 100;
+
+        let result = x.fixed_div_shl_floor(&env, &x, 64);
+
+        assert_eq!(result, 1 << 64);
+    }
+
+    /********** fixed_div_shl_ceil **********/
+
+    #[test]
+    fn test_fixed_div_shl_ceil_rounds_up() {
+        let env = Env::default();
+        let x: u128 = 1 << 64;
+        let y: u128 = 3 << 64;
+
+        let result = x.fixed_div_shl_ceil(&env, &y, 64);
+
+        assert_eq!(result, 6148914691236517206);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to shift left with overflow")]
+    fn test_fixed_div_shl_ceil_overflow() {
+        let env = Env::default();
+        let x: u128 = u128::MAX;
+
+        x.fixed_div_shl_ceil(&env, &x, 129);
+    }
+    /********** scaled_sqrt_mul **********/
+
+    #[test]
+    fn test_scaled_sqrt_mul_rounds_down() {
+        let env = Env::default();
+
+        let result = crate::u128::scaled_sqrt_mul(&env, 3, 5);
+
+        assert_eq!(result, 3);
+    }
+
+    #[test]
+    fn test_scaled_sqrt_mul_phantom_overflow_scales() {
+        let env = Env::default();
+        let x: u128 = u128::MAX;
+        let y: u128 = u128::MAX - 2;
+
+        let result = crate::u128::scaled_sqrt_mul(&env, x, y);
+
+        assert_eq!(result, u128::MAX - 2);
+    }
+
+    /********** budget **********/
+
+    #[test]
+    fn test_fixed_mul_floor_phantom_overflow_budget() {
+        let env = Env::default();
+        let x: u128 = u128::MAX / 3;
+        let y: u128 = 1_000_000_000_000_000_000;
+        let denominator: u128 = 1_000_000_000_000_000_001;
+
+        let (result, cpu, mem) = measure(&env, || x.fixed_mul_floor(&env, &y, &denominator));
+        let (expected, baseline_cpu, baseline_mem) = measure(&env, || {
+            baseline_u128_mul_div_floor(&env, x, y, denominator)
+        });
+
+        assert_eq!(result, expected);
+        assert!(cpu < baseline_cpu);
+        assert!(mem < baseline_mem);
+    }
+
+    #[test]
+    fn test_fixed_mul_ceil_phantom_overflow_budget() {
+        let env = Env::default();
+        let x: u128 = u128::MAX / 3;
+        let y: u128 = 1_000_000_000_000_000_000;
+        let denominator: u128 = 1_000_000_000_000_000_001;
+
+        let (result, cpu, mem) = measure(&env, || x.fixed_mul_ceil(&env, &y, &denominator));
+        let (expected, baseline_cpu, baseline_mem) =
+            measure(&env, || baseline_u128_mul_div_ceil(&env, x, y, denominator));
+
+        assert_eq!(result, expected);
+        assert!(cpu < baseline_cpu);
+        assert!(mem < baseline_mem);
+    }
 }
@@ -1,6 +1,6 @@
-use soroban_sdk::{Env, U256};
+use soroban_sdk::{unwrap::UnwrapOptimized, Env, U256};
 
-use crate::soroban_fixed_point::SorobanFixedPoint;
+use crate::soroban_fixed_point::{SorobanFixedPoint, SorobanFixedPointExt};
 
 impl SorobanFixedPoint for U256 {
     fn fixed_mul_floor(&self, _env: &Env, y: &U256, denominator: &U256) -> U256 {
@@ -20,24 +20,270 @@ impl SorobanFixedPoint for U256 {
     }
 }
 
+impl SorobanFixedPointExt for U256 {
+    fn gross_up(&self, env: &Env, fee_rate: &U256, scale: &U256) -> U256 {
+        let complement = fee_complement(fee_rate, scale);
+        mul_div_ceil(env, self, scale, &complement)
+    }
+
+    fn net_down(&self, _env: &Env, fee_rate: &U256, scale: &U256) -> U256 {
+        let complement = fee_complement(fee_rate, scale);
+        mul_div_floor(self, &complement, scale)
+    }
+
+    fn fixed_mul_shr_floor(&self, _env: &Env, y: &U256, shift: u32) -> U256 {
+        mul_shr_floor(self, y, shift)
+    }
+
+    fn fixed_mul_shr_ceil(&self, env: &Env, y: &U256, shift: u32) -> U256 {
+        mul_shr_ceil(env, self, y, shift)
+    }
+
+    fn fixed_div_shl_floor(&self, _env: &Env, y: &U256, shift: u32) -> U256 {
+        shl_checked(self, shift).div(y)
+    }
+
+    fn fixed_div_shl_ceil(&self, env: &Env, y: &U256, shift: u32) -> U256 {
+        div_ceil(env, &shl_checked(self, shift), y)
+    }
+}
+
 /// Performs floor(x * y / z)
 pub(crate) fn mul_div_floor(x: &U256, y: &U256, z: &U256) -> U256 {
     // floor taken by default
-    x.mul(&y).div(&z)
+    x.mul(y).div(z)
 }
 
 /// Performs ceil(x * y / z)
 pub(crate) fn mul_div_ceil(env: &Env, x: &U256, y: &U256, z: &U256) -> U256 {
-    let r = x.mul(&y);
-    let remainder = r.rem_euclid(&z);
+    div_ceil(env, &x.mul(y), z)
+}
+
+/// Performs ceil(r / z)
+pub(crate) fn div_ceil(env: &Env, r: &U256, z: &U256) -> U256 {
+    // a small value, which does not allocate a host object
+    let zero = U256::from_u32(env, 0);
+    let quotient = r.div(z);
+    // floor taken by default, so only an inexact result is adjusted. The remainder costs less than
+    // multiplying the quotient back.
+    if r.rem_euclid(z) != zero {
+        quotient.add(&U256::from_u32(env, 1))
+    } else {
+        quotient
+    }
+}
+
+/// Performs floor(x * y / z) with a 512 bit intermediate product
+///
+/// ### Panics
+/// If z is 0 or the result does not fit in a U256
+pub(crate) fn mul_div_full_floor(env: &Env, x: &U256, y: &U256, z: &U256) -> U256 {
+    let (quotient, _) = mul_div_full(x, y, z);
+    from_limbs(env, quotient)
+}
+
+/// Performs ceil(x * y / z) with a 512 bit intermediate product
+///
+/// ### Panics
+/// If z is 0 or the result does not fit in a U256
+pub(crate) fn mul_div_full_ceil(env: &Env, x: &U256, y: &U256, z: &U256) -> U256 {
+    let (mut quotient, inexact) = mul_div_full(x, y, z);
+    if inexact {
+        // add one, carrying into the higher limbs
+        let mut i = 0;
+        loop {
+            if i == 4 {
+                panic!("attempt to multiply with overflow");
+            }
+            let (limb, carry) = quotient[i].overflowing_add(1);
+            quotient[i] = limb;
+            if !carry {
+                break;
+            }
+            i += 1;
+        }
+    }
+    from_limbs(env, quotient)
+}
+
+/// Performs floor(x * y / z) natively on 64 bit limbs, least significant first. Returns the
+/// quotient and whether the division was inexact.
+fn mul_div_full(x: &U256, y: &U256, z: &U256) -> ([u64; 4], bool) {
+    let (x, y, z) = (to_limbs(x), to_limbs(y), to_limbs(z));
+    if z == [0; 4] {
+        panic!("attempt to divide by zero");
+    }
+    let mut product = [0u64; 8];
+    for i in 0..4 {
+        let mut carry: u128 = 0;
+        for j in 0..4 {
+            let t = product[i + j] as u128 + x[i] as u128 * y[j] as u128 + carry;
+            product[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        product[i + 4] = carry as u64;
+    }
+
+    // binary long division, starting from the highest set bit of the product
+    let bits = match product.iter().rposition(|&limb| limb != 0) {
+        Some(i) => i as u32 * 64 + 64 - product[i].leading_zeros(),
+        None => return ([0; 4], false),
+    };
+    let mut quotient = [0u64; 8];
+    let mut rem = [0u64; 4];
+    for bit in (0..bits as usize).rev() {
+        // the remainder is less than z, so it fits in 257 bits after the shift
+        let overflow = rem[3] >> 63 == 1;
+        for i in (1..4).rev() {
+            rem[i] = (rem[i] << 1) | (rem[i - 1] >> 63);
+        }
+        rem[0] = (rem[0] << 1) | ((product[bit / 64] >> (bit % 64)) & 1);
+        if overflow || !less_than(&rem, &z) {
+            let mut borrow = false;
+            for i in 0..4 {
+                let (limb, b1) = rem[i].overflowing_sub(z[i]);
+                let (limb, b2) = limb.overflowing_sub(borrow as u64);
+                rem[i] = limb;
+                borrow = b1 || b2;
+            }
+            quotient[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+    if quotient[4..] != [0; 4] {
+        panic!("attempt to multiply with overflow");
+    }
+    (
+        [quotient[0], quotient[1], quotient[2], quotient[3]],
+        rem != [0; 4],
+    )
+}
+
+/// Returns true if a < b, for 64 bit limbs least significant first
+fn less_than(a: &[u64; 4], b: &[u64; 4]) -> bool {
+    for i in (0..4).rev() {
+        if a[i] != b[i] {
+            return a[i] < b[i];
+        }
+    }
+    false
+}
+
+/// Converts x to 64 bit limbs, least significant first
+fn to_limbs(x: &U256) -> [u64; 4] {
+    let mut buf = [0u8; 32];
+    x.to_be_bytes().copy_into_slice(&mut buf);
+    let mut limbs = [0u64; 4];
+    for (i, chunk) in buf.rchunks(8).enumerate() {
+        limbs[i] = u64::from_be_bytes(chunk.try_into().unwrap_optimized());
+    }
+    limbs
+}
+
+/// Converts 64 bit limbs, least significant first, to a U256
+fn from_limbs(env: &Env, limbs: [u64; 4]) -> U256 {
+    U256::from_parts(env, limbs[3], limbs[2], limbs[1], limbs[0])
+}
+
+/// Performs floor(x * y / 2^shift)
+pub(crate) fn mul_shr_floor(x: &U256, y: &U256, shift: u32) -> U256 {
+    x.mul(y).shr(shift)
+}
+
+/// Performs ceil(x * y / 2^shift)
+pub(crate) fn mul_shr_ceil(env: &Env, x: &U256, y: &U256, shift: u32) -> U256 {
+    let r = x.mul(y);
+    let quotient = r.shr(shift);
+    // any bits dropped by the shift are the remainder
+    if quotient.shl(shift) == r {
+        quotient
+    } else {
+        quotient.add(&U256::from_u32(env, 1))
+    }
+}
+
+/// Performs x * 2^shift
+///
+/// ### Panics
+/// If the result does not fit in a U256
+pub(crate) fn shl_checked(x: &U256, shift: u32) -> U256 {
+    // the host drops any bits shifted out, so verify the shift is reversible
+    let r = x.shl(shift);
+    if r.shr(shift) != *x {
+        panic!("attempt to shift left with overflow");
+    }
+    r
+}
+
+/// Converts x to a U256 from its 64 bit parts, which costs less than `U256::from_u128`
+pub(crate) fn from_u128(env: &Env, x: u128) -> U256 {
+    U256::from_parts(env, 0, 0, (x >> 64) as u64, x as u64)
+}
+
+/// Converts x to a u128 from its big endian bytes, which costs less than `U256::to_u128`, or
+/// None if x does not fit in a u128
+pub(crate) fn to_u128(x: &U256) -> Option<u128> {
+    let mut buf = [0u8; 32];
+    x.to_be_bytes().copy_into_slice(&mut buf);
+    let (hi, lo) = buf.split_at(16);
+    if u128::from_be_bytes(hi.try_into().unwrap_optimized()) == 0 {
+        Some(u128::from_be_bytes(lo.try_into().unwrap_optimized()))
+    } else {
+        None
+    }
+}
+
+/// Returns the number of bits required to represent x, or 0 if x is 0
+pub(crate) fn bit_length(env: &Env, x: &U256) -> u32 {
     let zero = U256::from_u32(env, 0);
-    let one = U256::from_u32(env, 1);
-    r.div(&z).add(if remainder > zero { &one } else { &zero })
+    if *x == zero {
+        return 0;
+    }
+    // binary search for the smallest shift such that x >> bits == 0
+    let mut low: u32 = 0;
+    let mut bits: u32 = 256;
+    while bits - low > 1 {
+        let mid = (low + bits) / 2;
+        if x.shr(mid) == zero {
+            bits = mid;
+        } else {
+            low = mid;
+        }
+    }
+    bits
+}
+
+/// Performs floor(sqrt(x))
+pub(crate) fn sqrt_floor(env: &Env, x: &U256) -> U256 {
+    let bits = bit_length(env, x);
+    if bits == 0 {
+        return x.clone();
+    }
+    // start from an estimate at least as large as the root, so Newton's method converges downward
+    let mut z = U256::from_u32(env, 1).shl(bits.div_ceil(2));
+    loop {
+        let next = z.add(&x.div(&z)).shr(1);
+        if next >= z {
+            return z;
+        }
+        z = next;
+    }
+}
+
+/// Performs scale - fee_rate
+///
+/// ### Panics
+/// If fee_rate is not less than scale
+fn fee_complement(fee_rate: &U256, scale: &U256) -> U256 {
+    if fee_rate >= scale {
+        panic!("fee rate not less than scale");
+    }
+    scale.sub(fee_rate)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::budget::{baseline_u256_mul_div_ceil, measure};
 
     /********** fixed_mul_floor **********/
 
@@ -194,4 +440,276 @@ mod tests {
 
         x.fixed_div_ceil(&env, &y, &denominator);
     }
+
+    /********** gross_up **********/
+
+    #[test]
+    fn test_gross_up_above_u128() {
+        let env = Env::default();
+        let net: U256 = U256::from_u128(&env, u128::MAX);
+        let fee_rate: U256 = U256::from_u128(&env, 30000);
+        let scale: U256 = U256::from_u128(&env, 1_0000000);
+
+        let result = net.gross_up(&env, &fee_rate, &scale);
+
+        // 341306285778273283313314551085023281299
+        assert_eq!(
+            result,
+            U256::from_parts(&env, 0, 1, 55506752478564347, 16448500984481235091)
+        );
+        assert_eq!(result.net_down(&env, &fee_rate, &scale), net);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_gross_up_invalid_fee_rate() {
+        let env = Env::default();
+        let net: U256 = U256::from_u128(&env, 1_0000000);
+        let scale: U256 = U256::from_u128(&env, 1_0000000);
+
+        net.gross_up(&env, &scale, &scale);
+    }
+
+    /********** net_down **********/
+
+    #[test]
+    fn test_net_down_above_u128() {
+        let env = Env::default();
+        let gross: U256 = U256::from_u128(&env, u128::MAX).shl(64);
+        let fee_rate: U256 = U256::from_u128(&env, 30000);
+        let scale: U256 = U256::from_u128(&env, 1_0000000);
+
+        let result = gross.net_down(&env, &fee_rate, &scale);
+
+        // 6258270430180520721544282054938043416835656974289153986396
+        assert_eq!(
+            result,
+            U256::from_parts(
+                &env,
+                0,
+                18391403841488422961,
+                2803905099203851844,
+                11713682486805565276
+            )
+        );
+    }
+
+    /********** fixed_mul_shr_floor **********/
+
+    #[test]
+    fn test_fixed_mul_shr_floor_q96() {
+        let env = Env::default();
+        // 1.5 * 1.25 = 1.875
+        let x: U256 = U256::from_u128(&env, 3 << 95);
+        let y: U256 = U256::from_u128(&env, 5 << 94);
+
+        let result = x.fixed_mul_shr_floor(&env, &y, 96);
+
+        assert_eq!(result, U256::from_u128(&env, 15 << 93));
+    }
+
+    /********** fixed_mul_shr_ceil **********/
+
+    #[test]
+    fn test_fixed_mul_shr_ceil_rounds_up() {
+        let env = Env::default();
+        let x: U256 = U256::from_u128(&env, 3);
+        let y: U256 = U256::from_u128(&env, 1);
+
+        assert_eq!(x.fixed_mul_shr_ceil(&env, &y, 1), U256::from_u128(&env, 2));
+        assert_eq!(
+            x.fixed_mul_shr_ceil(&env, &y, 255),
+            U256::from_u128(&env, 1)
+        );
+    }
+
+    /********** fixed_div_shl_floor **********/
+
+    #[test]
+    fn test_fixed_div_shl_floor_rounds_down() {
+        let env = Env::default();
+        let x: U256 = U256::from_u128(&env, 1 << 96);
+        let y: U256 = U256::from_u128(&env, 3 << 96);
+
+        let result = x.fixed_div_shl_floor(&env, &y, 96);
+
+        assert_eq!(result, U256::from_u128(&env, 26409387504754779197847983445));
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to shift left with overflow")]
+    fn test_fixed_div_shl_floor_overflow() {
+        let env = Env::default();
+        let x: U256 = U256::from_u128(&env, 2);
+
+        x.fixed_div_shl_floor(&env, &x, 255);
+    }
+
+    /********** fixed_div_shl_ceil **********/
+
+    #[test]
+    fn test_fixed_div_shl_ceil_rounds_up() {
+        let env = Env::default();
+        let x: U256 = U256::from_u128(&env, 1 << 96);
+        let y: U256 = U256::from_u128(&env, 3 << 96);
+
+        let result = x.fixed_div_shl_ceil(&env, &y, 96);
+
+        assert_eq!(result, U256::from_u128(&env, 26409387504754779197847983446));
+    }
+
+    /********** mul_div_full **********/
+
+    #[test]
+    fn test_mul_div_full_512_bit_product() {
+        let env = Env::default();
+        let one = U256::from_u32(&env, 1);
+        let x = one.shl(255).add(&U256::from_u32(&env, 12345));
+        let y = one.shl(200).add(&U256::from_u32(&env, 7));
+        let z = one.shl(210).sub(&U256::from_u32(&env, 3));
+
+        let floor = mul_div_full_floor(&env, &x, &y, &z);
+        let ceil = mul_div_full_ceil(&env, &x, &y, &z);
+
+        assert_eq!(
+            floor,
+            U256::from_parts(&env, 0x20000000000000, 0, 0, 0xe0180000000c)
+        );
+        assert_eq!(ceil, floor.add(&one));
+    }
+
+    #[test]
+    fn test_mul_div_full_exact() {
+        let env = Env::default();
+        let x = U256::from_u32(&env, 1).shl(200);
+        let y = U256::from_u32(&env, 3).shl(100);
+        let z = U256::from_u32(&env, 1).shl(80);
+
+        let floor = mul_div_full_floor(&env, &x, &y, &z);
+        let ceil = mul_div_full_ceil(&env, &x, &y, &z);
+
+        assert_eq!(floor, U256::from_u32(&env, 3).shl(220));
+        assert_eq!(ceil, floor);
+    }
+
+    #[test]
+    fn test_mul_div_full_zero() {
+        let env = Env::default();
+        let zero = U256::from_u32(&env, 0);
+        let z = U256::from_u32(&env, 7);
+
+        assert_eq!(mul_div_full_ceil(&env, &zero, &z, &z), zero);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to multiply with overflow")]
+    fn test_mul_div_full_result_overflow() {
+        let env = Env::default();
+        let x = U256::from_u32(&env, 1).shl(255);
+        let y = U256::from_u32(&env, 2);
+
+        mul_div_full_floor(&env, &x, &y, &U256::from_u32(&env, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to divide by zero")]
+    fn test_mul_div_full_divide_by_zero() {
+        let env = Env::default();
+        let x = U256::from_u32(&env, 1);
+
+        mul_div_full_floor(&env, &x, &x, &U256::from_u32(&env, 0));
+    }
+
+    /********** bit_length **********/
+
+    #[test]
+    fn test_bit_length() {
+        let env = Env::default();
+
+        assert_eq!(bit_length(&env, &U256::from_u32(&env, 0)), 0);
+        assert_eq!(bit_length(&env, &U256::from_u32(&env, 1)), 1);
+        assert_eq!(bit_length(&env, &U256::from_u128(&env, u128::MAX)), 128);
+        assert_eq!(
+            bit_length(&env, &U256::from_parts(&env, u64::MAX, 0, 0, 0)),
+            256
+        );
+    }
+
+    /********** sqrt_floor **********/
+
+    #[test]
+    fn test_sqrt_floor_rounds_down() {
+        let env = Env::default();
+        let x: U256 = U256::from_u128(&env, 99);
+
+        let result = sqrt_floor(&env, &x);
+
+        assert_eq!(result, U256::from_u128(&env, 9));
+    }
+
+    #[test]
+    fn test_sqrt_floor_perfect_square() {
+        let env = Env::default();
+        let root: U256 = U256::from_u128(&env, u128::MAX);
+
+        let result = sqrt_floor(&env, &root.mul(&root));
+
+        assert_eq!(result, root);
+    }
+
+    #[test]
+    fn test_sqrt_floor_zero() {
+        let env = Env::default();
+
+        let result = sqrt_floor(&env, &U256::from_u32(&env, 0));
+
+        assert_eq!(result, U256::from_u32(&env, 0));
+    }
+
+    /********** budget **********/
+
+    #[test]
+    fn test_fixed_mul_ceil_budget() {
+        let env = Env::default();
+        let x: U256 = U256::from_u128(&env, 3 << 100);
+        let y: U256 = U256::from_u128(&env, 5 << 100);
+        let denominator: U256 = U256::from_u128(&env, 1 << 100);
+        let inexact: U256 = U256::from_u128(&env, (1 << 100) + 1);
+
+        // an exact result skips the addition
+        let (result, cpu, _) = measure(&env, || x.fixed_mul_ceil(&env, &y, &denominator));
+        let (expected, baseline_cpu, _) = measure(&env, || {
+            baseline_u256_mul_div_ceil(&env, &x, &y, &denominator)
+        });
+        assert_eq!(result, expected);
+        assert!(cpu < baseline_cpu);
+
+        let (result, cpu, _) = measure(&env, || x.fixed_mul_ceil(&env, &y, &inexact));
+        let (expected, baseline_cpu, _) =
+            measure(&env, || baseline_u256_mul_div_ceil(&env, &x, &y, &inexact));
+        assert_eq!(result, expected);
+        assert!(cpu <= baseline_cpu);
+    }
+
+    /********** conversions **********/
+
+    #[test]
+    fn test_from_u128() {
+        let env = Env::default();
+
+        for x in [0, 1, u128::MAX, 1 << 64] {
+            assert_eq!(from_u128(&env, x), U256::from_u128(&env, x));
+        }
+    }
+
+    #[test]
+    fn test_to_u128() {
+        let env = Env::default();
+
+        for x in [0, 1, u128::MAX, 1 << 64] {
+            assert_eq!(to_u128(&U256::from_u128(&env, x)), Some(x));
+        }
+        let max = U256::from_u128(&env, u128::MAX);
+        assert_eq!(to_u128(&max.add(&U256::from_u32(&env, 1))), None);
+    }
 }
@@ -20,7 +20,7 @@ impl FixedPoint for u64 {
 
 /// Performs floor(x * y / z)
 fn mul_div_floor(x: u64, y: u64, z: u64) -> Option<u64> {
-    return match x.checked_mul(y) {
+    match x.checked_mul(y) {
         Some(r) => r.checked_div(z),
         None => {
             let res_u128 = crate::u128::mul_div_floor(x as u128, y as u128, z as u128)?;
@@ -29,12 +29,12 @@ fn mul_div_floor(x: u64, y: u64, z: u64) -> Option<u64> {
             }
             Some(res_u128 as u64)
         }
-    };
+    }
 }
 
 /// Performs ceil(x * y / z)
 fn mul_div_ceil(x: u64, y: u64, z: u64) -> Option<u64> {
-    return match x.checked_mul(y) {
+    match x.checked_mul(y) {
         Some(r) => {
             let remainder = r.checked_rem_euclid(z)?;
             // div overflow will be caught by checked_rem_euclid
@@ -47,7 +47,7 @@ fn mul_div_ceil(x: u64, y: u64, z: u64) -> Option<u64> {
             }
             Some(res_u128 as u64)
         }
-    };
+    }
 }
 
 #[cfg(test)]
@@ -237,4 +237,67 @@ mod tests {
 
         assert_eq!(result, None);
     }
+
+    /********** gross_up **********/
+
+    #[test]
+    fn test_gross_up_rounds_up() {
+        // a 25 basis point fee
+        let net: u64 = 1_000_000;
+
+        let result = net.gross_up(25, 10_000).unwrap();
+
+        assert_eq!(result, 1_002_507)
+    }
+
+    #[test]
+    fn test_gross_up_is_inverse_of_net_down() {
+        let net: u64 = 1_000_000;
+
+        let gross = net.gross_up(25, 10_000).unwrap();
+
+        assert_eq!(gross.net_down(25, 10_000).unwrap(), net);
+        assert_eq!((gross - 1).net_down(25, 10_000).unwrap(), net - 1);
+    }
+
+    #[test]
+    fn test_gross_up_phantom_overflow_scales() {
+        let net: u64 = u64::MAX / 2;
+
+        let result = net.gross_up(25, 10_000).unwrap();
+
+        assert_eq!(result, 9_246_488_257_498_522_113)
+    }
+
+    #[test]
+    fn test_gross_up_overflow() {
+        let result = u64::MAX.gross_up(1, 10_000);
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn test_gross_up_invalid_fee_rate() {
+        let result = 1_000_000u64.gross_up(10_000, 10_000);
+
+        assert_eq!(result, None);
+    }
+
+    /********** net_down **********/
+
+    #[test]
+    fn test_net_down_rounds_down() {
+        let gross: u64 = 1_002_506;
+
+        let result = gross.net_down(25, 10_000).unwrap();
+
+        assert_eq!(result, 999_999)
+    }
+
+    #[test]
+    fn test_net_down_max() {
+        let result = u64::MAX.net_down(0, 10_000).unwrap();
+
+        assert_eq!(result, u64::MAX)
+    }
 }
@@ -0,0 +1,258 @@
+use soroban_sdk::{unwrap::UnwrapOptimized, Env};
+
+use crate::{Rounding, SorobanFixedPoint};
+
+// @dev - conversions follow ERC-4626. When a decimals offset is provided, the vault is treated as if it holds
+// 1 additional virtual asset and 10^offset additional virtual shares. This makes donation (inflation) attacks
+// against the first depositor unprofitable, as the attacker's donation is mostly captured by the virtual shares.
+// More detail can be found here: https://docs.openzeppelin.com/contracts/5.x/erc4626
+//
+// Conversions are done with `SorobanFixedPoint` so large totals do not cause a phantom overflow.
+
+/// Converts an amount of assets to shares, given the total assets held by the vault and the
+/// total supply of shares.
+///
+/// If `decimals_offset` is None and the vault has no shares, assets are converted 1:1.
+///
+/// Deposits should round shares down, and withdrawals should round shares up.
+///
+/// ### Panics
+/// If the vault has shares but no assets and `decimals_offset` is None, an overflow occurs,
+/// or the result does not fit in an i128.
+pub fn assets_to_shares(
+    env: &Env,
+    assets: i128,
+    total_assets: i128,
+    total_supply: i128,
+    decimals_offset: Option<u32>,
+    rounding: Rounding,
+) -> i128 {
+    let (virtual_assets, virtual_shares) = virtual_amounts(decimals_offset);
+    if virtual_shares == 0 && total_supply == 0 {
+        return assets;
+    }
+    mul_div(
+        env,
+        assets,
+        total_supply.checked_add(virtual_shares).unwrap_optimized(),
+        total_assets.checked_add(virtual_assets).unwrap_optimized(),
+        rounding,
+    )
+}
+
+/// Converts an amount of shares to assets, given the total assets held by the vault and the
+/// total supply of shares.
+///
+/// If `decimals_offset` is None and the vault has no shares, shares are converted 1:1.
+///
+/// Redemptions should round assets down, and mints should round assets up.
+///
+/// ### Panics
+/// If an overflow occurs or the result does not fit in an i128.
+pub fn shares_to_assets(
+    env: &Env,
+    shares: i128,
+    total_assets: i128,
+    total_supply: i128,
+    decimals_offset: Option<u32>,
+    rounding: Rounding,
+) -> i128 {
+    let (virtual_assets, virtual_shares) = virtual_amounts(decimals_offset);
+    if virtual_shares == 0 && total_supply == 0 {
+        return shares;
+    }
+    mul_div(
+        env,
+        shares,
+        total_assets.checked_add(virtual_assets).unwrap_optimized(),
+        total_supply.checked_add(virtual_shares).unwrap_optimized(),
+        rounding,
+    )
+}
+
+/// Returns the virtual (assets, shares) for a decimals offset
+fn virtual_amounts(decimals_offset: Option<u32>) -> (i128, i128) {
+    match decimals_offset {
+        Some(offset) => (1, 10i128.checked_pow(offset).unwrap_optimized()),
+        None => (0, 0),
+    }
+}
+
+/// Performs x * y / z, rounded according to `rounding`
+fn mul_div(env: &Env, x: i128, y: i128, z: i128, rounding: Rounding) -> i128 {
+    match rounding {
+        Rounding::Floor => x.fixed_mul_floor(env, &y, &z),
+        Rounding::Ceil => x.fixed_mul_ceil(env, &y, &z),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /********** assets_to_shares **********/
+
+    #[test]
+    fn test_assets_to_shares_empty_vault() {
+        let env = Env::default();
+
+        let result = assets_to_shares(&env, 100_0000000, 0, 0, None, Rounding::Floor);
+
+        assert_eq!(result, 100_0000000);
+    }
+
+    #[test]
+    fn test_assets_to_shares_rounding() {
+        let env = Env::default();
+        let total_assets: i128 = 1000_0000001;
+        let total_supply: i128 = 900_0000000;
+
+        let floor = assets_to_shares(
+            &env,
+            100_0000000,
+            total_assets,
+            total_supply,
+            None,
+            Rounding::Floor,
+        );
+        let ceil = assets_to_shares(
+            &env,
+            100_0000000,
+            total_assets,
+            total_supply,
+            None,
+            Rounding::Ceil,
+        );
+
+        assert_eq!(floor, 89_9999999);
+        assert_eq!(ceil, 90_0000000);
+    }
+
+    #[test]
+    fn test_assets_to_shares_large_supply_scales() {
+        let env = Env::default();
+        let total_assets: i128 = 10i128.pow(30);
+        let total_supply: i128 = 2 * 10i128.pow(30);
+
+        let result = assets_to_shares(
+            &env,
+            10i128.pow(20),
+            total_assets,
+            total_supply,
+            None,
+            Rounding::Floor,
+        );
+
+        assert_eq!(result, 2 * 10i128.pow(20));
+    }
+
+    #[test]
+    fn test_assets_to_shares_decimals_offset() {
+        let env = Env::default();
+
+        // empty vault mints 10^offset shares per asset
+        let result = assets_to_shares(&env, 100, 0, 0, Some(3), Rounding::Floor);
+
+        assert_eq!(result, 100_000);
+    }
+
+    #[test]
+    fn test_assets_to_shares_decimals_offset_prevents_inflation_attack() {
+        let env = Env::default();
+        // attacker deposits 1 asset, then donates 100_0000000 assets to the vault
+        let offset = Some(6);
+        let attacker_shares = assets_to_shares(&env, 1, 0, 0, offset, Rounding::Floor);
+        let total_assets: i128 = 1 + 100_0000000;
+
+        let victim_shares = assets_to_shares(
+            &env,
+            50_0000000,
+            total_assets,
+            attacker_shares,
+            offset,
+            Rounding::Floor,
+        );
+
+        // victim still receives shares, and the attacker cannot redeem more than the victim lost
+        assert!(victim_shares > 0);
+        let redeem = |shares: i128| {
+            shares_to_assets(
+                &env,
+                shares,
+                total_assets + 50_0000000,
+                attacker_shares + victim_shares,
+                offset,
+                Rounding::Floor,
+            )
+        };
+        let victim_loss = 50_0000000 - redeem(victim_shares);
+        let attacker_profit = redeem(attacker_shares) - total_assets;
+        assert!(attacker_profit <= victim_loss);
+        assert!(attacker_profit < 0);
+    }
+
+    /********** shares_to_assets **********/
+
+    #[test]
+    fn test_shares_to_assets_empty_vault() {
+        let env = Env::default();
+
+        let result = shares_to_assets(&env, 100_0000000, 0, 0, None, Rounding::Floor);
+
+        assert_eq!(result, 100_0000000);
+    }
+
+    #[test]
+    fn test_shares_to_assets_rounding() {
+        let env = Env::default();
+        let total_assets: i128 = 1000_0000001;
+        let total_supply: i128 = 900_0000000;
+
+        let floor = shares_to_assets(
+            &env,
+            90_0000000,
+            total_assets,
+            total_supply,
+            None,
+            Rounding::Floor,
+        );
+        let ceil = shares_to_assets(
+            &env,
+            90_0000000,
+            total_assets,
+            total_supply,
+            None,
+            Rounding::Ceil,
+        );
+
+        assert_eq!(floor, 100_0000000);
+        assert_eq!(ceil, 100_0000001);
+    }
+
+    #[test]
+    fn test_shares_to_assets_round_trip_favors_vault() {
+        let env = Env::default();
+        let total_assets: i128 = 1234_5678901;
+        let total_supply: i128 = 1000_0000000;
+        let offset = Some(3);
+
+        let shares = assets_to_shares(
+            &env,
+            7_7777777,
+            total_assets,
+            total_supply,
+            offset,
+            Rounding::Floor,
+        );
+        let assets = shares_to_assets(
+            &env,
+            shares,
+            total_assets,
+            total_supply,
+            offset,
+            Rounding::Floor,
+        );
+
+        assert!(assets <= 7_7777777);
+    }
+}
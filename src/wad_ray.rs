@@ -0,0 +1,470 @@
+use soroban_sdk::{unwrap::UnwrapOptimized, Env, I256, U256};
+
+use crate::{
+    i256,
+    scale::{RAY, SCALE_9, WAD},
+    u256,
+};
+
+// @dev - matches the WadRayMath library used by Aave, where a wad has 18 decimals and a ray has 27
+// decimals. More detail can be found here:
+// https://github.com/aave/aave-v3-core/blob/master/contracts/protocol/libraries/math/WadRayMath.sol
+//
+// Precision:
+// * all results are rounded half up, such that `wad_mul`, `wad_div`, `ray_mul`, `ray_div`, and
+//   `ray_to_wad` are bit identical to Aave for non-negative inputs
+// * for signed types, a negative result is also rounded half up (towards positive infinity), so
+//   -0.5 wei rounds to 0
+// * like Aave, `wad_div` and `ray_div` add floor(y / 2) before dividing, so an odd y rounds a
+//   result exactly halfway between two values down
+
+/// The ratio between a ray and a wad, 10^9
+const WAD_RAY_RATIO: i128 = SCALE_9;
+
+/// Wad and ray math trait for porting protocols that use Aave's WadRayMath.
+///
+/// The `i128` and `u128` implementations fall back to 256 bit math if an intermediate
+/// calculation overflows, and panic if the result does not fit.
+pub trait WadRayMath: Sized {
+    /// Calculates x * y / WAD, rounded half up.
+    ///
+    /// ### Panics
+    /// This method will panic if an overflow occurs or the result does not fit in Self.
+    fn wad_mul(&self, env: &Env, y: &Self) -> Self;
+
+    /// Calculates x * WAD / y, rounded half up.
+    ///
+    /// ### Panics
+    /// This method will panic if y is 0, an overflow occurs, or the result does not fit in Self.
+    fn wad_div(&self, env: &Env, y: &Self) -> Self;
+
+    /// Calculates x * y / RAY, rounded half up.
+    ///
+    /// ### Panics
+    /// This method will panic if an overflow occurs or the result does not fit in Self.
+    fn ray_mul(&self, env: &Env, y: &Self) -> Self;
+
+    /// Calculates x * RAY / y, rounded half up.
+    ///
+    /// ### Panics
+    /// This method will panic if y is 0, an overflow occurs, or the result does not fit in Self.
+    fn ray_div(&self, env: &Env, y: &Self) -> Self;
+
+    /// Converts x from a ray to a wad, rounded half up.
+    fn ray_to_wad(&self, env: &Env) -> Self;
+
+    /// Converts x from a wad to a ray.
+    ///
+    /// ### Panics
+    /// This method will panic if the result does not fit in Self.
+    fn wad_to_ray(&self, env: &Env) -> Self;
+}
+
+impl WadRayMath for i128 {
+    fn wad_mul(&self, env: &Env, y: &i128) -> i128 {
+        mul_div_half_up(env, *self, *y, WAD)
+    }
+
+    fn wad_div(&self, env: &Env, y: &i128) -> i128 {
+        mul_div_half_up(env, *self, WAD, *y)
+    }
+
+    fn ray_mul(&self, env: &Env, y: &i128) -> i128 {
+        mul_div_half_up(env, *self, *y, RAY)
+    }
+
+    fn ray_div(&self, env: &Env, y: &i128) -> i128 {
+        mul_div_half_up(env, *self, RAY, *y)
+    }
+
+    fn ray_to_wad(&self, env: &Env) -> i128 {
+        mul_div_half_up(env, *self, 1, WAD_RAY_RATIO)
+    }
+
+    fn wad_to_ray(&self, _env: &Env) -> i128 {
+        self.checked_mul(WAD_RAY_RATIO).unwrap_optimized()
+    }
+}
+
+/// Performs floor(x * y / z + 1/2), where the half is floor(|z| / 2)
+fn mul_div_half_up(env: &Env, x: i128, y: i128, z: i128) -> i128 {
+    let native = x.checked_mul(y).and_then(|r| {
+        // move the sign of the denominator to the numerator, so the half is always added
+        let (r, z) = if z < 0 {
+            (r.checked_neg()?, z.checked_neg()?)
+        } else {
+            (r, z)
+        };
+        r.checked_add(z / 2)?.checked_div_euclid(z)
+    });
+    match native {
+        Some(res) => res,
+        None => {
+            // scale to i256 and retry
+            let res = mul_div_half_up_i256(
+                env,
+                &i256::from_i128(env, x),
+                &i256::from_i128(env, y),
+                &i256::from_i128(env, z),
+            );
+            // will panic if result is not representable in i128
+            i256::to_i128(&res).unwrap_optimized()
+        }
+    }
+}
+
+impl WadRayMath for u128 {
+    fn wad_mul(&self, env: &Env, y: &u128) -> u128 {
+        mul_div_half_up_u128(env, *self, *y, WAD as u128)
+    }
+
+    fn wad_div(&self, env: &Env, y: &u128) -> u128 {
+        mul_div_half_up_u128(env, *self, WAD as u128, *y)
+    }
+
+    fn ray_mul(&self, env: &Env, y: &u128) -> u128 {
+        mul_div_half_up_u128(env, *self, *y, RAY as u128)
+    }
+
+    fn ray_div(&self, env: &Env, y: &u128) -> u128 {
+        mul_div_half_up_u128(env, *self, RAY as u128, *y)
+    }
+
+    fn ray_to_wad(&self, env: &Env) -> u128 {
+        mul_div_half_up_u128(env, *self, 1, WAD_RAY_RATIO as u128)
+    }
+
+    fn wad_to_ray(&self, _env: &Env) -> u128 {
+        self.checked_mul(WAD_RAY_RATIO as u128).unwrap_optimized()
+    }
+}
+
+/// Performs floor((x * y + floor(z / 2)) / z)
+fn mul_div_half_up_u128(env: &Env, x: u128, y: u128, z: u128) -> u128 {
+    let native = x
+        .checked_mul(y)
+        .and_then(|r| r.checked_add(z / 2))
+        .and_then(|r| r.checked_div(z));
+    match native {
+        Some(res) => res,
+        None => {
+            // scale to u256 and retry
+            let res = mul_div_half_up_u256(
+                env,
+                &u256::from_u128(env, x),
+                &u256::from_u128(env, y),
+                &u256::from_u128(env, z),
+            );
+            // will panic if result is not representable in u128
+            u256::to_u128(&res).unwrap_optimized()
+        }
+    }
+}
+
+impl WadRayMath for I256 {
+    fn wad_mul(&self, env: &Env, y: &I256) -> I256 {
+        mul_div_half_up_i256(env, self, y, &i256::from_i128(env, WAD))
+    }
+
+    fn wad_div(&self, env: &Env, y: &I256) -> I256 {
+        mul_div_half_up_i256(env, self, &i256::from_i128(env, WAD), y)
+    }
+
+    fn ray_mul(&self, env: &Env, y: &I256) -> I256 {
+        mul_div_half_up_i256(env, self, y, &i256::from_i128(env, RAY))
+    }
+
+    fn ray_div(&self, env: &Env, y: &I256) -> I256 {
+        mul_div_half_up_i256(env, self, &i256::from_i128(env, RAY), y)
+    }
+
+    fn ray_to_wad(&self, env: &Env) -> I256 {
+        let ratio = i256::from_i128(env, WAD_RAY_RATIO);
+        let half = i256::from_i128(env, WAD_RAY_RATIO / 2);
+        i256::div_floor(env, &self.add(&half), &ratio)
+    }
+
+    fn wad_to_ray(&self, env: &Env) -> I256 {
+        self.mul(&i256::from_i128(env, WAD_RAY_RATIO))
+    }
+}
+
+/// Performs floor(x * y / z + 1/2), where the half is floor(|z| / 2)
+fn mul_div_half_up_i256(env: &Env, x: &I256, y: &I256, z: &I256) -> I256 {
+    let zero = I256::from_i32(env, 0);
+    let r = x.mul(y);
+    // move the sign of the denominator to the numerator, so the half is always added
+    let (r, z) = if *z < zero {
+        (zero.sub(&r), zero.sub(z))
+    } else {
+        (r, z.clone())
+    };
+    let r = r.add(&z.div(&I256::from_i32(env, 2)));
+    i256::div_floor(env, &r, &z)
+}
+
+impl WadRayMath for U256 {
+    fn wad_mul(&self, env: &Env, y: &U256) -> U256 {
+        mul_div_half_up_u256(env, self, y, &u256::from_u128(env, WAD as u128))
+    }
+
+    fn wad_div(&self, env: &Env, y: &U256) -> U256 {
+        mul_div_half_up_u256(env, self, &u256::from_u128(env, WAD as u128), y)
+    }
+
+    fn ray_mul(&self, env: &Env, y: &U256) -> U256 {
+        mul_div_half_up_u256(env, self, y, &u256::from_u128(env, RAY as u128))
+    }
+
+    fn ray_div(&self, env: &Env, y: &U256) -> U256 {
+        mul_div_half_up_u256(env, self, &u256::from_u128(env, RAY as u128), y)
+    }
+
+    fn ray_to_wad(&self, env: &Env) -> U256 {
+        let ratio = u256::from_u128(env, WAD_RAY_RATIO as u128);
+        let half = u256::from_u128(env, WAD_RAY_RATIO as u128 / 2);
+        self.add(&half).div(&ratio)
+    }
+
+    fn wad_to_ray(&self, env: &Env) -> U256 {
+        self.mul(&u256::from_u128(env, WAD_RAY_RATIO as u128))
+    }
+}
+
+/// Performs floor((x * y + floor(z / 2)) / z)
+fn mul_div_half_up_u256(env: &Env, x: &U256, y: &U256, z: &U256) -> U256 {
+    let half = z.div(&U256::from_u32(env, 2));
+    x.mul(y).add(&half).div(z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /********** i128 **********/
+
+    #[test]
+    fn test_wad_mul_i128() {
+        let env = Env::default();
+        let x: i128 = 1_500_000_000_000_000_000;
+        let y: i128 = 2_333_333_333_333_333_333;
+
+        assert_eq!(x.wad_mul(&env, &y), 3_500_000_000_000_000_000);
+        assert_eq!(1i128.wad_mul(&env, &(WAD / 2)), 1);
+        assert_eq!(1i128.wad_mul(&env, &(WAD / 2 - 1)), 0);
+    }
+
+    #[test]
+    fn test_wad_mul_i128_negative_rounds_half_up() {
+        let env = Env::default();
+
+        assert_eq!((-1i128).wad_mul(&env, &(WAD / 2)), 0);
+        assert_eq!((-1i128).wad_mul(&env, &(WAD / 2 + 1)), -1);
+        assert_eq!(1i128.wad_mul(&env, &(-WAD / 2 - 1)), -1);
+    }
+
+    #[test]
+    fn test_wad_mul_i128_phantom_overflow_scales() {
+        let env = Env::default();
+
+        assert_eq!(i128::MAX.wad_mul(&env, &WAD), i128::MAX);
+        assert_eq!(i128::MIN.wad_mul(&env, &WAD), i128::MIN);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wad_mul_i128_result_overflow() {
+        let env = Env::default();
+
+        i128::MAX.wad_mul(&env, &(2 * WAD));
+    }
+
+    #[test]
+    fn test_wad_div_i128() {
+        let env = Env::default();
+        let x: i128 = 1_000_000_000_000_000_000;
+        let y: i128 = 3_000_000_000_000_000_000;
+
+        assert_eq!(x.wad_div(&env, &y), 333_333_333_333_333_333);
+        assert_eq!((2 * x).wad_div(&env, &y), 666_666_666_666_666_667);
+        assert_eq!(x.wad_div(&env, &-y), -333_333_333_333_333_333);
+        assert_eq!((2 * x).wad_div(&env, &-y), -666_666_666_666_666_667);
+    }
+
+    #[test]
+    fn test_wad_div_i128_odd_divisor() {
+        let env = Env::default();
+
+        // 2 * WAD / 3 is 666_666_666_666_666_666.667
+        assert_eq!(2i128.wad_div(&env, &3), 666_666_666_666_666_667);
+        // floor(3 / 2) is added, so the exact half 0.5 rounds down
+        assert_eq!(1i128.wad_div(&env, &(2 * WAD + 1)), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wad_div_i128_zero() {
+        let env = Env::default();
+
+        WAD.wad_div(&env, &0);
+    }
+
+    #[test]
+    fn test_ray_mul_div_i128() {
+        let env = Env::default();
+        let index: i128 = 1_050_000_000_000_000_000_000_000_000;
+        let amount: i128 = 1_000_000_000_000_000_000_001;
+
+        let scaled = amount.ray_div(&env, &index);
+
+        assert_eq!(scaled, 952_380_952_380_952_380_953);
+        assert_eq!(scaled.ray_mul(&env, &index), 1_000_000_000_000_000_000_001);
+    }
+
+    #[test]
+    fn test_ray_wad_conversions_i128() {
+        let env = Env::default();
+
+        assert_eq!(WAD.wad_to_ray(&env), RAY);
+        assert_eq!(RAY.ray_to_wad(&env), WAD);
+        assert_eq!(500_000_000i128.ray_to_wad(&env), 1);
+        assert_eq!(499_999_999i128.ray_to_wad(&env), 0);
+        assert_eq!((-500_000_000i128).ray_to_wad(&env), 0);
+        assert_eq!((-500_000_001i128).ray_to_wad(&env), -1);
+        assert_eq!(i128::MAX.ray_to_wad(&env), i128::MAX / WAD_RAY_RATIO + 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wad_to_ray_i128_overflow() {
+        let env = Env::default();
+
+        (i128::MAX / WAD_RAY_RATIO + 1).wad_to_ray(&env);
+    }
+
+    /********** u128 **********/
+
+    #[test]
+    fn test_wad_mul_div_u128() {
+        let env = Env::default();
+        let x: u128 = 1_500_000_000_000_000_000;
+        let y: u128 = 2_333_333_333_333_333_333;
+
+        assert_eq!(x.wad_mul(&env, &y), 3_500_000_000_000_000_000);
+        assert_eq!(1u128.wad_mul(&env, &(WAD as u128 / 2)), 1);
+        assert_eq!(2u128.wad_div(&env, &3), 666_666_666_666_666_667);
+    }
+
+    #[test]
+    fn test_wad_ray_u128_phantom_overflow_scales() {
+        let env = Env::default();
+
+        assert_eq!(u128::MAX.wad_mul(&env, &(WAD as u128)), u128::MAX);
+        assert_eq!(u128::MAX.ray_div(&env, &(RAY as u128)), u128::MAX);
+        assert_eq!(
+            u128::MAX.ray_to_wad(&env),
+            u128::MAX / WAD_RAY_RATIO as u128 + 1
+        );
+    }
+
+    #[test]
+    fn test_ray_wad_conversions_u128() {
+        let env = Env::default();
+
+        assert_eq!((WAD as u128).wad_to_ray(&env), RAY as u128);
+        assert_eq!(1_499_999_999u128.ray_to_wad(&env), 1);
+        assert_eq!(1_500_000_000u128.ray_to_wad(&env), 2);
+    }
+
+    /********** I256 **********/
+
+    #[test]
+    fn test_wad_ray_i256() {
+        let env = Env::default();
+        let x = I256::from_i128(&env, 1_500_000_000_000_000_000);
+        let y = I256::from_i128(&env, 2_333_333_333_333_333_333);
+
+        assert_eq!(
+            x.wad_mul(&env, &y),
+            I256::from_i128(&env, 3_500_000_000_000_000_000)
+        );
+        assert_eq!(
+            I256::from_i128(&env, 2 * WAD).wad_div(&env, &I256::from_i128(&env, -3 * WAD)),
+            I256::from_i128(&env, -666_666_666_666_666_667)
+        );
+        assert_eq!(
+            I256::from_i128(&env, -1).ray_mul(&env, &I256::from_i128(&env, RAY / 2)),
+            I256::from_i128(&env, 0)
+        );
+        assert_eq!(
+            I256::from_i128(&env, 1).ray_div(&env, &I256::from_i128(&env, 3)),
+            I256::from_i128(&env, 333_333_333_333_333_333_333_333_333)
+        );
+        assert_eq!(
+            I256::from_i128(&env, -500_000_001).ray_to_wad(&env),
+            I256::from_i128(&env, -1)
+        );
+        assert_eq!(
+            I256::from_i128(&env, WAD).wad_to_ray(&env),
+            I256::from_i128(&env, RAY)
+        );
+    }
+
+    #[test]
+    fn test_wad_mul_i256_matches_i128() {
+        let env = Env::default();
+        let values: [i128; 5] = [
+            0,
+            1,
+            -7,
+            999_999_999_999_999_999,
+            -2_500_000_000_000_000_001,
+        ];
+
+        for x in values {
+            for y in values {
+                let expected = x.wad_mul(&env, &y);
+                let result = I256::from_i128(&env, x).wad_mul(&env, &I256::from_i128(&env, y));
+                assert_eq!(result, I256::from_i128(&env, expected));
+            }
+        }
+    }
+
+    /********** U256 **********/
+
+    #[test]
+    fn test_wad_ray_u256() {
+        let env = Env::default();
+        let x = U256::from_u128(&env, 1_500_000_000_000_000_000);
+        let y = U256::from_u128(&env, 2_333_333_333_333_333_333);
+
+        assert_eq!(
+            x.wad_mul(&env, &y),
+            U256::from_u128(&env, 3_500_000_000_000_000_000)
+        );
+        assert_eq!(
+            U256::from_u128(&env, 2).wad_div(&env, &U256::from_u128(&env, 3)),
+            U256::from_u128(&env, 666_666_666_666_666_667)
+        );
+        assert_eq!(
+            U256::from_u128(&env, u128::MAX).ray_mul(&env, &U256::from_u128(&env, RAY as u128)),
+            U256::from_u128(&env, u128::MAX)
+        );
+        assert_eq!(
+            U256::from_u128(&env, 1_500_000_000).ray_to_wad(&env),
+            U256::from_u128(&env, 2)
+        );
+        assert_eq!(
+            U256::from_u128(&env, WAD as u128).wad_to_ray(&env),
+            U256::from_u128(&env, RAY as u128)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wad_div_u256_zero() {
+        let env = Env::default();
+
+        U256::from_u32(&env, 1).wad_div(&env, &U256::from_u32(&env, 0));
+    }
+}
@@ -0,0 +1,121 @@
+//! Arbitrary precision reference model and conversions, shared by the property tests and the
+//! fuzz targets so both check against the same oracle.
+
+// each user only calls some of the helpers
+#![allow(dead_code)]
+
+use core::fmt::Debug;
+
+use num_bigint::{BigInt, Sign};
+use num_integer::Integer;
+use num_traits::{One, Signed, Zero};
+use soroban_sdk::{Bytes, Env, I256, U256};
+
+/********** reference model **********/
+
+/// Asserts `floor` and `ceil` equal floor(n / d) and ceil(n / d), or are None if the result
+/// is not representable. Both results must be None if `computable` is false.
+pub fn assert_rounded<T>(
+    n: &BigInt,
+    d: &BigInt,
+    computable: bool,
+    floor: Option<T>,
+    ceil: Option<T>,
+) where
+    T: Copy + Debug + PartialEq + Into<BigInt> + TryFrom<BigInt>,
+{
+    if d.is_zero() || !computable {
+        assert_eq!(floor, None);
+        assert_eq!(ceil, None);
+        return;
+    }
+    assert_eq!(
+        floor,
+        T::try_from(n.div_floor(d)).ok(),
+        "floor of {} / {}",
+        n,
+        d
+    );
+    assert_eq!(
+        ceil,
+        T::try_from(n.div_ceil(d)).ok(),
+        "ceil of {} / {}",
+        n,
+        d
+    );
+    if let (Some(floor), Some(ceil)) = (floor, ceil) {
+        assert_bounds(n, d, &floor.into(), &ceil.into());
+    }
+}
+
+/// Asserts floor <= n / d <= ceil and ceil - floor is 0 or 1
+pub fn assert_bounds(n: &BigInt, d: &BigInt, floor: &BigInt, ceil: &BigInt) {
+    // multiply through by |d| to compare without division
+    let (n, d) = if d.is_negative() {
+        (-n, -d)
+    } else {
+        (n.clone(), d.clone())
+    };
+    assert!(floor * &d <= n, "floor {} > {} / {}", floor, n, d);
+    assert!(ceil * &d >= n, "ceil {} < {} / {}", ceil, n, d);
+    let difference = ceil - floor;
+    assert!(difference.is_zero() || difference.is_one());
+}
+
+/// Returns true if `n` can be represented by T
+pub fn fits<T: TryFrom<BigInt>>(n: &BigInt) -> bool {
+    T::try_from(n.clone()).is_ok()
+}
+
+/// Returns 2^shift
+pub fn pow2(shift: u32) -> BigInt {
+    BigInt::one() << shift
+}
+
+/// Returns the fee complement `scale - fee_rate`, or None if `fee_rate` is not within [0, scale)
+pub fn fee_complement(fee_rate: &BigInt, scale: &BigInt) -> Option<BigInt> {
+    if fee_rate.is_negative() || fee_rate >= scale {
+        return None;
+    }
+    Some(scale - fee_rate)
+}
+
+/// Returns true if `n` can be represented by an I256
+pub fn fits_i256(n: &BigInt) -> bool {
+    let bound = BigInt::one() << 255;
+    n >= &-&bound && n < &bound
+}
+
+/// Returns true if `n` can be represented by a U256
+pub fn fits_u256(n: &BigInt) -> bool {
+    !n.is_negative() && n.bits() <= 256
+}
+
+/********** conversions **********/
+
+pub fn to_i256(env: &Env, n: &BigInt) -> I256 {
+    let bytes = n.to_signed_bytes_be();
+    let fill = if n.is_negative() { 0xff } else { 0 };
+    let mut buf = [fill; 32];
+    buf[32 - bytes.len()..].copy_from_slice(&bytes);
+    I256::from_be_bytes(env, &Bytes::from_slice(env, &buf))
+}
+
+pub fn from_i256(n: &I256) -> BigInt {
+    let mut buf = [0u8; 32];
+    n.to_be_bytes().copy_into_slice(&mut buf);
+    BigInt::from_signed_bytes_be(&buf)
+}
+
+pub fn to_u256(env: &Env, n: &BigInt) -> U256 {
+    let (_, bytes) = n.to_bytes_be();
+    let mut buf = [0u8; 32];
+    buf[32 - bytes.len()..].copy_from_slice(&bytes);
+    U256::from_be_bytes(env, &Bytes::from_slice(env, &buf))
+}
+
+pub fn from_u256(n: &U256) -> BigInt {
+    let mut buf = [0u8; 32];
+    n.to_be_bytes().copy_into_slice(&mut buf);
+    BigInt::from_bytes_be(Sign::Plus, &buf)
+}
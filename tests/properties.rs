@@ -0,0 +1,321 @@
+//! Property tests that check every `FixedPoint` and `SorobanFixedPoint` operation against an
+//! arbitrary precision reference model.
+
+mod common;
+
+use common::*;
+use num_bigint::{BigInt, Sign};
+use num_integer::Integer;
+use num_traits::Zero;
+use proptest::prelude::*;
+use soroban_fixed_point_math::{FixedPoint, SorobanFixedPoint, SorobanFixedPointExt};
+use soroban_sdk::{Env, I256, U256};
+
+/********** strategies **********/
+
+/// Values of T biased toward small numbers, powers of two, and the bounds of T
+macro_rules! native_strategy {
+    ($name:ident, $t:ty) => {
+        fn $name() -> BoxedStrategy<$t> {
+            prop_oneof![
+                4 => any::<$t>(),
+                2 => (0..1_0000000_0000000u64).prop_map(|x| x as $t),
+                2 => (any::<u32>(), 0..<$t>::BITS).prop_map(|(x, shift)| (x as $t) << shift),
+                1 => (0u8..4).prop_map(|x| (x as $t).wrapping_neg()),
+                1 => prop_oneof![Just(<$t>::MIN), Just(<$t>::MAX), Just(0 as $t), Just(1 as $t)],
+            ]
+            .boxed()
+        }
+    };
+}
+
+native_strategy!(i64_strategy, i64);
+native_strategy!(u64_strategy, u64);
+native_strategy!(i128_strategy, i128);
+native_strategy!(u128_strategy, u128);
+
+/// A random integer with at most `bits` bits, which is negative if `signed` and the sign bit is set
+fn bigint_strategy(bits: u32, signed: bool) -> impl Strategy<Value = BigInt> {
+    (any::<[u8; 32]>(), 0..=bits, any::<bool>()).prop_map(move |(bytes, len, negative)| {
+        let n = BigInt::from_bytes_be(Sign::Plus, &bytes) >> (256 - len);
+        if signed && negative {
+            -n
+        } else {
+            n
+        }
+    })
+}
+
+/// A pair of random integers whose product fits in 255 bits
+fn bigint_pair_strategy(signed: bool) -> impl Strategy<Value = (BigInt, BigInt)> {
+    (0..=254u32).prop_flat_map(move |bits| {
+        (
+            bigint_strategy(bits, signed),
+            bigint_strategy(254 - bits, signed),
+        )
+    })
+}
+
+/********** FixedPoint **********/
+
+/// Property tests for a `FixedPoint` implementation. If `widened`, the product of two values is
+/// always computed without overflow, otherwise operations return None if the product overflows.
+macro_rules! fixed_point_properties {
+    ($name:ident, $t:ty, $strategy:ident, $widened:expr) => {
+        mod $name {
+            use super::*;
+
+            fn computable(x: $t, y: $t) -> bool {
+                $widened || fits::<$t>(&(BigInt::from(x) * BigInt::from(y)))
+            }
+
+            proptest! {
+                #[test]
+                fn fixed_mul(x in $strategy(), y in $strategy(), denominator in $strategy()) {
+                    assert_rounded(
+                        &(BigInt::from(x) * BigInt::from(y)),
+                        &BigInt::from(denominator),
+                        computable(x, y),
+                        x.fixed_mul_floor(y, denominator),
+                        x.fixed_mul_ceil(y, denominator),
+                    );
+                }
+
+                #[test]
+                fn fixed_div(x in $strategy(), y in $strategy(), denominator in $strategy()) {
+                    assert_rounded(
+                        &(BigInt::from(x) * BigInt::from(denominator)),
+                        &BigInt::from(y),
+                        computable(x, denominator),
+                        x.fixed_div_floor(y, denominator),
+                        x.fixed_div_ceil(y, denominator),
+                    );
+                }
+
+                #[test]
+                fn gross_up_and_net_down(x in $strategy(), fee_rate in $strategy(), scale in $strategy()) {
+                    let (big_x, big_scale) = (BigInt::from(x), BigInt::from(scale));
+                    let gross_up = x.gross_up(fee_rate, scale);
+                    let net_down = x.net_down(fee_rate, scale);
+                    match fee_complement(&BigInt::from(fee_rate), &big_scale) {
+                        Some(complement) => {
+                            let expected_gross_up = if computable(x, scale) {
+                                <$t>::try_from((&big_x * &big_scale).div_ceil(&complement)).ok()
+                            } else {
+                                None
+                            };
+                            let expected_net_down = if computable(x, scale - fee_rate) {
+                                <$t>::try_from((&big_x * &complement).div_floor(&big_scale)).ok()
+                            } else {
+                                None
+                            };
+                            prop_assert_eq!(gross_up, expected_gross_up);
+                            prop_assert_eq!(net_down, expected_net_down);
+                        }
+                        None => {
+                            prop_assert_eq!(gross_up, None);
+                            prop_assert_eq!(net_down, None);
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
+
+fixed_point_properties!(fixed_point_i64, i64, i64_strategy, true);
+fixed_point_properties!(fixed_point_u64, u64, u64_strategy, true);
+fixed_point_properties!(fixed_point_i128, i128, i128_strategy, false);
+fixed_point_properties!(fixed_point_u128, u128, u128_strategy, false);
+
+/********** SorobanFixedPoint for i128 and u128 **********/
+
+/// Property tests for a `SorobanFixedPoint` implementation on a native type. Inputs are only
+/// tested if the result is representable, as the operations panic otherwise.
+macro_rules! soroban_native_properties {
+    ($name:ident, $t:ty, $strategy:ident) => {
+        mod $name {
+            use super::*;
+
+            /// Asserts the floor and ceil of n / d are computed, if representable
+            fn check(
+                n: &BigInt,
+                d: &BigInt,
+                floor: impl FnOnce() -> $t,
+                ceil: impl FnOnce() -> $t,
+            ) -> Result<(), TestCaseError> {
+                prop_assume!(!d.is_zero());
+                let expected_floor = n.div_floor(d);
+                let expected_ceil = n.div_ceil(d);
+                prop_assume!(fits::<$t>(&expected_floor) && fits::<$t>(&expected_ceil));
+                let (floor, ceil) = (BigInt::from(floor()), BigInt::from(ceil()));
+                prop_assert_eq!(&floor, &expected_floor);
+                prop_assert_eq!(&ceil, &expected_ceil);
+                assert_bounds(n, d, &floor, &ceil);
+                Ok(())
+            }
+
+            proptest! {
+                #[test]
+                fn fixed_mul(x in $strategy(), y in $strategy(), denominator in $strategy()) {
+                    let env = Env::default();
+                    check(
+                        &(BigInt::from(x) * BigInt::from(y)),
+                        &BigInt::from(denominator),
+                        || SorobanFixedPoint::fixed_mul_floor(&x, &env, &y, &denominator),
+                        || SorobanFixedPoint::fixed_mul_ceil(&x, &env, &y, &denominator),
+                    )?;
+                }
+
+                #[test]
+                fn fixed_div(x in $strategy(), y in $strategy(), denominator in $strategy()) {
+                    let env = Env::default();
+                    check(
+                        &(BigInt::from(x) * BigInt::from(denominator)),
+                        &BigInt::from(y),
+                        || SorobanFixedPoint::fixed_div_floor(&x, &env, &y, &denominator),
+                        || SorobanFixedPoint::fixed_div_ceil(&x, &env, &y, &denominator),
+                    )?;
+                }
+
+                #[test]
+                fn fixed_mul_shr(x in $strategy(), y in $strategy(), shift in 0..256u32) {
+                    let env = Env::default();
+                    check(
+                        &(BigInt::from(x) * BigInt::from(y)),
+                        &pow2(shift),
+                        || SorobanFixedPointExt::fixed_mul_shr_floor(&x, &env, &y, shift),
+                        || SorobanFixedPointExt::fixed_mul_shr_ceil(&x, &env, &y, shift),
+                    )?;
+                }
+
+                #[test]
+                fn fixed_div_shl(x in $strategy(), y in $strategy(), shift in 0..128u32) {
+                    let env = Env::default();
+                    check(
+                        &(BigInt::from(x) * pow2(shift)),
+                        &BigInt::from(y),
+                        || SorobanFixedPointExt::fixed_div_shl_floor(&x, &env, &y, shift),
+                        || SorobanFixedPointExt::fixed_div_shl_ceil(&x, &env, &y, shift),
+                    )?;
+                }
+
+                #[test]
+                fn gross_up_and_net_down(x in $strategy(), scale in 1..<$t>::MAX, fee_seed in any::<$t>()) {
+                    let env = Env::default();
+                    let fee_rate = fee_seed.rem_euclid(scale);
+                    let complement = BigInt::from(scale - fee_rate);
+                    let (big_x, big_scale) = (BigInt::from(x), BigInt::from(scale));
+
+                    let expected = (&big_x * &big_scale).div_ceil(&complement);
+                    if fits::<$t>(&expected) {
+                        prop_assert_eq!(BigInt::from(SorobanFixedPointExt::gross_up(&x, &env, &fee_rate, &scale)), expected);
+                    }
+                    let expected = (&big_x * &complement).div_floor(&big_scale);
+                    if fits::<$t>(&expected) {
+                        prop_assert_eq!(BigInt::from(SorobanFixedPointExt::net_down(&x, &env, &fee_rate, &scale)), expected);
+                    }
+                }
+            }
+        }
+    };
+}
+
+soroban_native_properties!(soroban_i128, i128, i128_strategy);
+soroban_native_properties!(soroban_u128, u128, u128_strategy);
+
+/********** SorobanFixedPoint for I256 and U256 **********/
+
+/// Property tests for a `SorobanFixedPoint` implementation on a host type. Inputs are chosen
+/// such that intermediate products fit in 255 bits, so every result is representable.
+macro_rules! soroban_host_properties {
+    ($name:ident, $t:ty, $signed:expr, $to:ident, $from:ident) => {
+        mod $name {
+            use super::*;
+
+            /// Asserts the floor and ceil of n / d are computed
+            fn check(n: &BigInt, d: &BigInt, floor: &$t, ceil: &$t) -> Result<(), TestCaseError> {
+                let (floor, ceil) = ($from(floor), $from(ceil));
+                prop_assert_eq!(&floor, &n.div_floor(d));
+                prop_assert_eq!(&ceil, &n.div_ceil(d));
+                assert_bounds(n, d, &floor, &ceil);
+                Ok(())
+            }
+
+            proptest! {
+                #[test]
+                fn fixed_mul((x, y) in bigint_pair_strategy($signed), denominator in bigint_strategy(255, $signed)) {
+                    prop_assume!(!denominator.is_zero());
+                    let env = Env::default();
+                    let (hx, hy, hd) = ($to(&env, &x), $to(&env, &y), $to(&env, &denominator));
+                    check(
+                        &(&x * &y),
+                        &denominator,
+                        &hx.fixed_mul_floor(&env, &hy, &hd),
+                        &hx.fixed_mul_ceil(&env, &hy, &hd),
+                    )?;
+                }
+
+                #[test]
+                fn fixed_div((x, denominator) in bigint_pair_strategy($signed), y in bigint_strategy(255, $signed)) {
+                    prop_assume!(!y.is_zero());
+                    let env = Env::default();
+                    let (hx, hy, hd) = ($to(&env, &x), $to(&env, &y), $to(&env, &denominator));
+                    check(
+                        &(&x * &denominator),
+                        &y,
+                        &hx.fixed_div_floor(&env, &hy, &hd),
+                        &hx.fixed_div_ceil(&env, &hy, &hd),
+                    )?;
+                }
+
+                #[test]
+                fn fixed_mul_shr((x, y) in bigint_pair_strategy($signed), shift in 0..256u32) {
+                    let env = Env::default();
+                    let (hx, hy) = ($to(&env, &x), $to(&env, &y));
+                    check(
+                        &(&x * &y),
+                        &pow2(shift),
+                        &hx.fixed_mul_shr_floor(&env, &hy, shift),
+                        &hx.fixed_mul_shr_ceil(&env, &hy, shift),
+                    )?;
+                }
+
+                #[test]
+                fn fixed_div_shl(
+                    (x, shift) in (0..=254u32).prop_flat_map(|bits| (bigint_strategy(bits, $signed), Just(254 - bits))),
+                    y in bigint_strategy(255, $signed),
+                ) {
+                    prop_assume!(!y.is_zero());
+                    let env = Env::default();
+                    let (hx, hy) = ($to(&env, &x), $to(&env, &y));
+                    check(
+                        &(&x * pow2(shift)),
+                        &y,
+                        &hx.fixed_div_shl_floor(&env, &hy, shift),
+                        &hx.fixed_div_shl_ceil(&env, &hy, shift),
+                    )?;
+                }
+
+                #[test]
+                fn gross_up_and_net_down((x, scale) in bigint_pair_strategy(false), fee_seed in bigint_strategy(255, false), negative in any::<bool>()) {
+                    prop_assume!(!scale.is_zero());
+                    let env = Env::default();
+                    let x = if $signed && negative { -x } else { x };
+                    let fee_rate = fee_seed % &scale;
+                    let complement = &scale - &fee_rate;
+                    let (hx, hf, hs) = ($to(&env, &x), $to(&env, &fee_rate), $to(&env, &scale));
+
+                    let gross_up = $from(&hx.gross_up(&env, &hf, &hs));
+                    let net_down = $from(&hx.net_down(&env, &hf, &hs));
+
+                    prop_assert_eq!(gross_up, (&x * &scale).div_ceil(&complement));
+                    prop_assert_eq!(net_down, (&x * &complement).div_floor(&scale));
+                }
+            }
+        }
+    };
+}
+
+soroban_host_properties!(soroban_i256, I256, true, to_i256, from_i256);
+soroban_host_properties!(soroban_u256, U256, false, to_u256, from_u256);